@@ -0,0 +1,1607 @@
+// 不用unsafe的双向链表
+// sixth的节点各自分配，链接是裸指针，每一处解引用都得自己保证安全
+// 这里换个做法：节点全放在一个Vec里，链接存下标，借用检查器能管到所有访问
+// 下标越界、链接错了最多panic，不会有UB，整个模块forbid(unsafe_code)
+//
+// 下标用u32，从1开始，0代表没有（Option<NonZeroU32>和u32一样大），一个节点的链接只占8字节
+// 删掉的槽位串成空闲链表，push时优先复用；链表空了就整块清掉（容量留着）
+//
+// 接口照着sixth::LinkedList（除了分配器、节点缓存和句柄），换个类型别名就能来回切换
+// 代价：
+// 1.节点不能在两个Vec之间搬，append、split、splice都要把元素一个个挪过去
+//   append和split_off挪短的那一边，O(min)；splice挪input，O(input.len)
+// 2.同时交出多个&mut T只能靠切片，所以iter_mut先把槽位按链表顺序整理一遍，O(n)
+//   整理完就是slice::IterMut，顺带让后面的遍历对缓存更友好
+// 3.sort也是先整理，再直接对槽位做稳定排序，T会被移动
+#![forbid(unsafe_code)]
+
+use alloc::vec::Vec;
+use core::{
+    cmp::Ordering,
+    fmt::{ self, Debug },
+    hash::{ Hash, Hasher },
+    mem,
+    num::NonZeroU32,
+    slice,
+};
+
+type Id = NonZeroU32;
+type Link = Option<Id>;
+
+pub struct LinkedList<T> {
+    slots: Vec<Slot<T>>,
+    front: Link,
+    back: Link,
+    // 空闲链表头
+    free: Link,
+    len: usize,
+}
+
+enum Slot<T> {
+    Node(Node<T>),
+    // 下一个空闲槽位
+    Free(Link),
+}
+
+// 和sixth一样，front是前一个，back是后一个
+struct Node<T> {
+    front: Link,
+    back: Link,
+    elem: T,
+}
+
+pub struct IntoIter<T> {
+    list: LinkedList<T>,
+}
+
+// 只借了槽位数组，按链接走
+pub struct Iter<'a, T> {
+    slots: &'a [Slot<T>],
+    front: Link,
+    back: Link,
+    len: usize,
+}
+
+// 槽位已经整理成链表顺序了，直接用切片的迭代器
+pub struct IterMut<'a, T> {
+    slots: slice::IterMut<'a, Slot<T>>,
+}
+
+// 光标，和sixth一样有个ghost位置（在back和front之间）
+pub struct CursorMut<'a, T> {
+    list: &'a mut LinkedList<T>,
+    cur: Link,
+    index: Option<usize>,
+}
+
+// 边走边摘，摘下满足条件的节点并吐出元素
+pub struct ExtractIf<'a, T, F: FnMut(&mut T) -> bool> {
+    list: &'a mut LinkedList<T>,
+    it: Link,
+    remaining: usize,
+    pred: F,
+}
+
+// 只读光标
+pub struct Cursor<'a, T> {
+    list: &'a LinkedList<T>,
+    cur: Link,
+    index: Option<usize>,
+}
+
+// 槽位 ===========================================
+fn index(id: Id) -> usize {
+    id.get() as usize - 1
+}
+
+// 第i个槽位的id
+fn id_at(i: usize) -> Id {
+    Id::new(i as u32 + 1).unwrap()
+}
+
+fn node<T>(slots: &[Slot<T>], id: Id) -> &Node<T> {
+    match &slots[index(id)] {
+        Slot::Node(node) => node,
+        Slot::Free(_) => unreachable!("link to a free slot"),
+    }
+}
+
+fn node_mut<T>(slots: &mut [Slot<T>], id: Id) -> &mut Node<T> {
+    match &mut slots[index(id)] {
+        Slot::Node(node) => node,
+        Slot::Free(_) => unreachable!("link to a free slot"),
+    }
+}
+
+impl<T> LinkedList<T> {
+    pub fn new() -> Self {
+        Self::with_capacity(0)
+    }
+
+    // 预先分配capacity个槽位
+    pub fn with_capacity(capacity: usize) -> Self {
+        LinkedList {
+            slots: Vec::with_capacity(capacity),
+            front: None,
+            back: None,
+            free: None,
+            len: 0,
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.slots.capacity()
+    }
+
+    // 整理掉空闲槽位，再把多余的容量还掉
+    pub fn shrink_to_fit(&mut self) {
+        self.linearize();
+        self.slots.shrink_to_fit();
+    }
+
+    pub fn push_front(&mut self, elem: T) {
+        let new = self.alloc(Node {
+            front: None,
+            back: self.front,
+            elem,
+        });
+        match self.front {
+            Some(old) => self.node_mut(old).front = Some(new),
+            None => self.back = Some(new),
+        }
+        self.front = Some(new);
+        self.len += 1;
+    }
+
+    pub fn push_back(&mut self, elem: T) {
+        let new = self.alloc(Node {
+            front: self.back,
+            back: None,
+            elem,
+        });
+        match self.back {
+            Some(old) => self.node_mut(old).back = Some(new),
+            None => self.front = Some(new),
+        }
+        self.back = Some(new);
+        self.len += 1;
+    }
+
+    pub fn pop_front(&mut self) -> Option<T> {
+        let id = self.front?;
+        self.unlink(id);
+        Some(self.release(id))
+    }
+
+    pub fn pop_back(&mut self) -> Option<T> {
+        let id = self.back?;
+        self.unlink(id);
+        Some(self.release(id))
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn front(&self) -> Option<&T> {
+        self.front.map(|id| &self.node(id).elem)
+    }
+
+    pub fn front_mut(&mut self) -> Option<&mut T> {
+        self.front.map(|id| &mut self.node_mut(id).elem)
+    }
+
+    pub fn back(&self) -> Option<&T> {
+        self.back.map(|id| &self.node(id).elem)
+    }
+
+    pub fn back_mut(&mut self) -> Option<&mut T> {
+        self.back.map(|id| &mut self.node_mut(id).elem)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    // 先把链表置空再清槽位，元素的drop panic了链表也是空的
+    pub fn clear(&mut self) {
+        self.front = None;
+        self.back = None;
+        self.free = None;
+        self.len = 0;
+        self.slots.clear();
+    }
+
+    // 把other整个接到后面，other变空
+    // 挪短的那一边：other短就把other的元素挪过来，否则把自己的挪到other前面再交换
+    pub fn append(&mut self, other: &mut Self) {
+        if self.len >= other.len {
+            while let Some(elem) = other.pop_front() {
+                self.push_back(elem);
+            }
+        } else {
+            while let Some(elem) = self.pop_back() {
+                other.push_front(elem);
+            }
+            mem::swap(self, other);
+        }
+    }
+
+    // 把other整个接到前面，other变空
+    pub fn prepend(&mut self, other: &mut Self) {
+        if self.len >= other.len {
+            while let Some(elem) = other.pop_back() {
+                self.push_front(elem);
+            }
+        } else {
+            while let Some(elem) = self.pop_front() {
+                other.push_back(elem);
+            }
+            mem::swap(self, other);
+        }
+    }
+
+    // 从at处分成两半，返回[at, len)，自己留下[0, at)
+    // 同样挪短的那一半，O(min(at, len - at))
+    // at > len 会panic
+    pub fn split_off(&mut self, at: usize) -> LinkedList<T> {
+        assert!(at <= self.len, "Cannot split off at a nonexistent index");
+        let mut other = Self::new();
+        if self.len - at <= at {
+            for _ in at..self.len {
+                other.push_front(self.pop_back().unwrap());
+            }
+        } else {
+            for _ in 0..at {
+                other.push_back(self.pop_front().unwrap());
+            }
+            mem::swap(self, &mut other);
+        }
+        other
+    }
+
+    // 返回[0, at)，自己留下[at, len)
+    fn split_front(&mut self, at: usize) -> LinkedList<T> {
+        let mut back = self.split_off(at);
+        mem::swap(self, &mut back);
+        back
+    }
+
+    // 保留pred返回true的，其余的按顺序drop掉
+    pub fn retain<F: FnMut(&T) -> bool>(&mut self, mut pred: F) {
+        self.retain_mut(|elem| pred(elem))
+    }
+
+    pub fn retain_mut<F: FnMut(&mut T) -> bool>(&mut self, mut pred: F) {
+        for _ in self.extract_if(|elem| !pred(elem)) {}
+    }
+
+    // 懒的，迭代到哪摘到哪
+    pub fn extract_if<F: FnMut(&mut T) -> bool>(&mut self, pred: F) -> ExtractIf<'_, T, F> {
+        ExtractIf {
+            it: self.front,
+            remaining: self.len,
+            list: self,
+            pred,
+        }
+    }
+
+    pub fn sort(&mut self)
+    where
+        T: Ord,
+    {
+        self.sort_by(T::cmp)
+    }
+
+    pub fn sort_by_key<K: Ord, F: FnMut(&T) -> K>(&mut self, mut f: F) {
+        self.sort_by(|a, b| f(a).cmp(&f(b)))
+    }
+
+    // 整理之后第i个槽位就是第i个元素，直接对槽位做稳定排序
+    // 槽位里的链接跟着一起挪乱了，排完由SortGuard按位置重写；比较函数panic了也一样，只是顺序打乱了
+    pub fn sort_by<F: FnMut(&T, &T) -> Ordering>(&mut self, mut compare: F) {
+        if self.len < 2 {
+            return;
+        }
+        self.linearize();
+        let guard = SortGuard(self);
+        guard.0.slots.sort_by(|a, b| match (a, b) {
+            (Slot::Node(a), Slot::Node(b)) => compare(&a.elem, &b.elem),
+            _ => unreachable!("free slot after linearize"),
+        });
+    }
+
+    pub fn is_sorted_by<F: FnMut(&T, &T) -> bool>(&self, mut compare: F) -> bool {
+        let mut iter = self.into_iter();
+        let mut last = match iter.next() {
+            Some(elem) => elem,
+            None => {
+                return true;
+            }
+        };
+        for elem in iter {
+            if !compare(last, elem) {
+                return false;
+            }
+            last = elem;
+        }
+        true
+    }
+
+    pub fn cursor_mut(&mut self) -> CursorMut<'_, T> {
+        CursorMut {
+            list: self,
+            cur: None,
+            index: None,
+        }
+    }
+
+    // 指向front，空链表则指向ghost
+    pub fn cursor_front_mut(&mut self) -> CursorMut<'_, T> {
+        CursorMut {
+            cur: self.front,
+            index: self.front.map(|_| 0),
+            list: self,
+        }
+    }
+
+    // 指向back，空链表则指向ghost
+    pub fn cursor_back_mut(&mut self) -> CursorMut<'_, T> {
+        CursorMut {
+            cur: self.back,
+            index: self.back.map(|_| self.len - 1),
+            list: self,
+        }
+    }
+
+    // 指向index处的元素，从离得近的一头走过去
+    // index == len 指向ghost，再大就panic
+    pub fn cursor_at_mut(&mut self, index: usize) -> CursorMut<'_, T> {
+        assert!(index <= self.len, "Cannot move cursor to a nonexistent index");
+        CursorMut {
+            cur: self.node_at(index),
+            index: Some(index).filter(|&index| index < self.len),
+            list: self,
+        }
+    }
+
+    pub fn cursor_front(&self) -> Cursor<'_, T> {
+        Cursor {
+            list: self,
+            cur: self.front,
+            index: self.front.map(|_| 0),
+        }
+    }
+
+    pub fn cursor_back(&self) -> Cursor<'_, T> {
+        Cursor {
+            list: self,
+            cur: self.back,
+            index: self.back.map(|_| self.len - 1),
+        }
+    }
+
+    // 内部 ===========================================
+    fn node(&self, id: Id) -> &Node<T> {
+        node(&self.slots, id)
+    }
+
+    fn node_mut(&mut self, id: Id) -> &mut Node<T> {
+        node_mut(&mut self.slots, id)
+    }
+
+    // 有空闲槽位就拿空闲链表头上那个，没有才往数组末尾加
+    fn alloc(&mut self, node: Node<T>) -> Id {
+        match self.free {
+            Some(id) => {
+                match mem::replace(&mut self.slots[index(id)], Slot::Node(node)) {
+                    Slot::Free(next) => self.free = next,
+                    Slot::Node(_) => unreachable!("free list points at a node"),
+                }
+                id
+            }
+            None => {
+                assert!(self.slots.len() < u32::MAX as usize, "Arena list is limited to u32::MAX slots");
+                self.slots.push(Slot::Node(node));
+                id_at(self.slots.len() - 1)
+            }
+        }
+    }
+
+    // 槽位变成Free挂到空闲链表头上，下一次alloc最先拿到它
+    // 最后一个元素也走了就清空整个数组（容量不动），省得留一串全是Free的槽位
+    // 调用前要先unlink
+    fn release(&mut self, id: Id) -> T {
+        let slot = mem::replace(&mut self.slots[index(id)], Slot::Free(self.free));
+        self.free = Some(id);
+        if self.len == 0 {
+            self.slots.clear();
+            self.free = None;
+        }
+        match slot {
+            Slot::Node(node) => node.elem,
+            Slot::Free(_) => unreachable!("released a free slot"),
+        }
+    }
+
+    // 把id从链表上摘下来，len减一
+    fn unlink(&mut self, id: Id) {
+        let Node { front: prev, back: next, .. } = *self.node(id);
+        match prev {
+            Some(prev) => self.node_mut(prev).back = next,
+            None => self.front = next,
+        }
+        match next {
+            Some(next) => self.node_mut(next).front = prev,
+            None => self.back = prev,
+        }
+        self.len -= 1;
+    }
+
+    // 从离得近的一头开始找，最多走len/2步
+    fn node_at(&self, index: usize) -> Link {
+        if index >= self.len {
+            return None;
+        }
+        if index <= self.len / 2 {
+            let mut cur = self.front;
+            for _ in 0..index {
+                cur = self.node(cur?).back;
+            }
+            cur
+        } else {
+            let mut cur = self.back;
+            for _ in 0..self.len - 1 - index {
+                cur = self.node(cur?).front;
+            }
+            cur
+        }
+    }
+
+    // 把槽位整理成链表顺序：第i个元素放到第i个槽位，后面的空闲槽位截掉
+    // 从front开始，把第i个节点和第i个槽位里的东西交换，前i个槽位就一直是排好的
+    // 空闲链表在交换中会乱掉，反正最后整个不要了
+    fn linearize(&mut self) {
+        let mut cur = self.front;
+        for i in 0..self.len {
+            let id = cur.unwrap();
+            let want = id_at(i);
+            if id != want {
+                self.swap_slots(want, id);
+            }
+            cur = self.node(want).back;
+        }
+        self.slots.truncate(self.len);
+        self.free = None;
+    }
+
+    // 交换两个槽位，两边是节点的话把指向它们的链接都改过来
+    // a、b可能相邻，所以先把节点自己的链接里的a、b对调，再去改邻居
+    fn swap_slots(&mut self, a: Id, b: Id) {
+        let swap = |link: Link| match link {
+            Some(id) if id == a => Some(b),
+            Some(id) if id == b => Some(a),
+            link => link,
+        };
+        self.slots.swap(index(a), index(b));
+        for at in [a, b] {
+            let Slot::Node(node) = &mut self.slots[index(at)] else {
+                continue;
+            };
+            node.front = swap(node.front);
+            node.back = swap(node.back);
+            let Node { front: prev, back: next, .. } = *node;
+            // 邻居是另一个被交换的节点的话，它自己那一轮会改
+            match prev {
+                Some(prev) if prev == a || prev == b => {}
+                Some(prev) => self.node_mut(prev).back = Some(at),
+                None => self.front = Some(at),
+            }
+            match next {
+                Some(next) if next == a || next == b => {}
+                Some(next) => self.node_mut(next).front = Some(at),
+                None => self.back = Some(at),
+            }
+        }
+    }
+
+    // 槽位已经是链表顺序，按位置重写所有链接
+    fn relink(&mut self) {
+        let len = self.len;
+        for (i, slot) in self.slots.iter_mut().enumerate() {
+            let node = match slot {
+                Slot::Node(node) => node,
+                Slot::Free(_) => unreachable!("free slot after linearize"),
+            };
+            node.front = i.checked_sub(1).map(id_at);
+            node.back = (i + 1 < len).then(|| id_at(i + 1));
+        }
+        self.front = (len > 0).then(|| id_at(0));
+        self.back = len.checked_sub(1).map(id_at);
+    }
+}
+
+struct SortGuard<'a, T>(&'a mut LinkedList<T>);
+
+impl<'a, T> Drop for SortGuard<'a, T> {
+    fn drop(&mut self) {
+        self.0.relink();
+    }
+}
+
+// 迭代器 =========================================
+// IntoIter =======================================
+impl<T> IntoIterator for LinkedList<T> {
+    type IntoIter = IntoIter<T>;
+    type Item = T;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter {
+            list: self,
+        }
+    }
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.list.pop_front()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.list.len, Some(self.list.len))
+    }
+}
+
+impl<T> DoubleEndedIterator for IntoIter<T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.list.pop_back()
+    }
+}
+
+impl<T> ExactSizeIterator for IntoIter<T> {
+    fn len(&self) -> usize {
+        self.list.len
+    }
+}
+// Iter ===========================================
+impl<'a, T> IntoIterator for &'a LinkedList<T> {
+    type IntoIter = Iter<'a, T>;
+    type Item = &'a T;
+
+    fn into_iter(self) -> Self::IntoIter {
+        Iter {
+            slots: &self.slots,
+            front: self.front,
+            back: self.back,
+            len: self.len,
+        }
+    }
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        let node = node(self.slots, self.front?);
+        self.len -= 1;
+        self.front = node.back;
+        Some(&node.elem)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for Iter<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        let node = node(self.slots, self.back?);
+        self.len -= 1;
+        self.back = node.front;
+        Some(&node.elem)
+    }
+}
+
+impl<'a, T> ExactSizeIterator for Iter<'a, T> {
+    fn len(&self) -> usize {
+        self.len
+    }
+}
+
+// 只有切片和几个下标，复制一份互不影响；不要求T: Clone，所以不derive
+impl<'a, T> Clone for Iter<'a, T> {
+    fn clone(&self) -> Self {
+        Iter { ..*self }
+    }
+}
+// IterMut ========================================
+// 要先整理槽位，所以要&mut，O(n)
+impl<'a, T> IntoIterator for &'a mut LinkedList<T> {
+    type IntoIter = IterMut<'a, T>;
+    type Item = &'a mut T;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.linearize();
+        IterMut {
+            slots: self.slots.iter_mut(),
+        }
+    }
+}
+
+fn elem_mut<T>(slot: &mut Slot<T>) -> &mut T {
+    match slot {
+        Slot::Node(node) => &mut node.elem,
+        Slot::Free(_) => unreachable!("free slot after linearize"),
+    }
+}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.slots.next().map(elem_mut)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.slots.size_hint()
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for IterMut<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.slots.next_back().map(elem_mut)
+    }
+}
+
+impl<'a, T> ExactSizeIterator for IterMut<'a, T> {
+    fn len(&self) -> usize {
+        self.slots.len()
+    }
+}
+// ExtractIf ======================================
+impl<'a, T, F: FnMut(&mut T) -> bool> Iterator for ExtractIf<'a, T, F> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(id) = self.it {
+            // 先往后走，再判断，摘掉id也不影响it
+            self.it = self.list.node(id).back;
+            self.remaining -= 1;
+            if (self.pred)(&mut self.list.node_mut(id).elem) {
+                self.list.unlink(id);
+                return Some(self.list.release(id));
+            }
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.remaining))
+    }
+}
+
+// 光标 ===========================================
+// 移动、插入、删除和sixth一样是O(1)
+// split、splice要挪元素，见开头
+impl<'a, T> CursorMut<'a, T> {
+    pub fn index(&self) -> Option<usize> {
+        self.index
+    }
+
+    // 再借一个只读光标，借期内CursorMut不能动
+    pub fn as_cursor(&self) -> Cursor<'_, T> {
+        Cursor {
+            list: self.list,
+            cur: self.cur,
+            index: self.index,
+        }
+    }
+
+    pub fn move_next(&mut self) {
+        if let Some(cur) = self.cur {
+            self.cur = self.list.node(cur).back;
+            if self.cur.is_some() {
+                *self.index.as_mut().unwrap() += 1;
+            } else {
+                self.index = None;
+            }
+        } else if !self.list.is_empty() {
+            self.cur = self.list.front;
+            self.index = Some(0);
+        }
+    }
+
+    pub fn move_prev(&mut self) {
+        if let Some(cur) = self.cur {
+            self.cur = self.list.node(cur).front;
+            if self.cur.is_some() {
+                *self.index.as_mut().unwrap() -= 1;
+            } else {
+                self.index = None;
+            }
+        } else if !self.list.is_empty() {
+            self.cur = self.list.back;
+            self.index = Some(self.list.len - 1);
+        }
+    }
+
+    // 跳到index处，index == len 是ghost，再大就panic
+    // 从当前位置、front、back三个起点里挑最近的走
+    pub fn seek(&mut self, index: usize) {
+        let len = self.list.len;
+        assert!(index <= len, "Cannot move cursor to a nonexistent index");
+        if index == len {
+            self.cur = None;
+            self.index = None;
+            return;
+        }
+
+        let from_ends = index.min(len - 1 - index);
+        match (self.cur, self.index) {
+            (Some(mut cur), Some(cur_index)) if cur_index.abs_diff(index) < from_ends => {
+                if index > cur_index {
+                    for _ in cur_index..index {
+                        cur = self.list.node(cur).back.unwrap();
+                    }
+                } else {
+                    for _ in index..cur_index {
+                        cur = self.list.node(cur).front.unwrap();
+                    }
+                }
+                self.cur = Some(cur);
+            }
+            _ => {
+                self.cur = self.list.node_at(index);
+            }
+        }
+        self.index = Some(index);
+    }
+
+    // 相当于n为正时move_next n次，为负时move_prev -n次
+    // ghost也算一个位置，所以是在len + 1个位置的环上走
+    pub fn move_by(&mut self, n: isize) {
+        let ring = self.list.len + 1;
+        let pos = self.index.unwrap_or(self.list.len);
+        let steps = n.rem_euclid(ring as isize) as usize;
+        self.seek((pos + steps) % ring);
+    }
+
+    pub fn current(&mut self) -> Option<&mut T> {
+        self.cur.map(|id| &mut self.list.node_mut(id).elem)
+    }
+
+    pub fn peek_next(&mut self) -> Option<&mut T> {
+        let next = match self.cur {
+            Some(cur) => self.list.node(cur).back,
+            None => self.list.front,
+        };
+        next.map(|id| &mut self.list.node_mut(id).elem)
+    }
+
+    pub fn peek_prev(&mut self) -> Option<&mut T> {
+        let prev = match self.cur {
+            Some(cur) => self.list.node(cur).front,
+            None => self.list.back,
+        };
+        prev.map(|id| &mut self.list.node_mut(id).elem)
+    }
+
+    // 在当前元素后插入，ghost上则插到front
+    // index不变
+    pub fn insert_after(&mut self, elem: T) {
+        let Some(cur) = self.cur else {
+            return self.list.push_front(elem);
+        };
+        let Some(next) = self.list.node(cur).back else {
+            return self.list.push_back(elem);
+        };
+        let new = self.list.alloc(Node {
+            front: Some(cur),
+            back: Some(next),
+            elem,
+        });
+        self.list.node_mut(cur).back = Some(new);
+        self.list.node_mut(next).front = Some(new);
+        self.list.len += 1;
+    }
+
+    // 在当前元素前插入，ghost上则插到back
+    // 当前元素往后挪了一位，所以index加1
+    pub fn insert_before(&mut self, elem: T) {
+        let Some(cur) = self.cur else {
+            return self.list.push_back(elem);
+        };
+        match self.list.node(cur).front {
+            Some(prev) => {
+                let new = self.list.alloc(Node {
+                    front: Some(prev),
+                    back: Some(cur),
+                    elem,
+                });
+                self.list.node_mut(prev).back = Some(new);
+                self.list.node_mut(cur).front = Some(new);
+                self.list.len += 1;
+            }
+            None => self.list.push_front(elem),
+        }
+        *self.index.as_mut().unwrap() += 1;
+    }
+
+    // 移除当前元素，光标移到下一个（back方向）
+    // 下一个是ghost的话index变None，否则index不变
+    pub fn remove_current(&mut self) -> Option<T> {
+        let cur = self.cur?;
+        let next = self.list.node(cur).back;
+        self.list.unlink(cur);
+        self.cur = next;
+        if next.is_none() {
+            self.index = None;
+        }
+        Some(self.list.release(cur))
+    }
+
+    // 同remove_current，元素装进一个新链表返回
+    pub fn remove_current_as_list(&mut self) -> Option<LinkedList<T>> {
+        let elem = self.remove_current()?;
+        let mut list = LinkedList::with_capacity(1);
+        list.push_back(elem);
+        Some(list)
+    }
+
+    // 替换当前元素，返回旧值
+    // ghost上没有元素可换，原样把elem还回去
+    pub fn replace_current(&mut self, elem: T) -> Result<T, T> {
+        match self.current() {
+            Some(cur) => Ok(mem::replace(cur, elem)),
+            None => Err(elem),
+        }
+    }
+
+    // 返回当前元素之前的部分，光标留在原来的元素上，index变0
+    // ghost上则整个链表交出去
+    // 挪的是短的一半，当前元素可能换了槽位，所以重新从front取
+    pub fn split_before(&mut self) -> LinkedList<T> {
+        match self.index {
+            Some(index) => {
+                let output = self.list.split_front(index);
+                self.cur = self.list.front;
+                self.index = Some(0);
+                output
+            }
+            None => mem::take(self.list),
+        }
+    }
+
+    // 返回当前元素之后的部分，光标和index不变
+    pub fn split_after(&mut self) -> LinkedList<T> {
+        match self.index {
+            Some(index) => {
+                let output = self.list.split_off(index + 1);
+                self.cur = self.list.back;
+                output
+            }
+            None => mem::take(self.list),
+        }
+    }
+
+    // 插到当前元素之前，index加input.len
+    // ghost上则接到back，光标还在ghost上
+    pub fn splice_before(&mut self, input: LinkedList<T>) {
+        if self.list.is_empty() {
+            // 自己是空的，直接换成input，不用挪
+            *self.list = input;
+            return;
+        }
+        for elem in input {
+            self.insert_before(elem);
+        }
+    }
+
+    // 插到当前元素之后，index不变
+    // ghost上则接到front
+    pub fn splice_after(&mut self, input: LinkedList<T>) {
+        if self.list.is_empty() {
+            *self.list = input;
+            return;
+        }
+        for elem in input.into_iter().rev() {
+            self.insert_after(elem);
+        }
+    }
+}
+
+// 只读光标 =======================================
+// 返回的引用是'a的，不受光标本身借用的约束
+impl<'a, T> Cursor<'a, T> {
+    pub fn index(&self) -> Option<usize> {
+        self.index
+    }
+
+    pub fn move_next(&mut self) {
+        if let Some(cur) = self.cur {
+            self.cur = self.list.node(cur).back;
+            if self.cur.is_some() {
+                *self.index.as_mut().unwrap() += 1;
+            } else {
+                self.index = None;
+            }
+        } else if !self.list.is_empty() {
+            self.cur = self.list.front;
+            self.index = Some(0);
+        }
+    }
+
+    pub fn move_prev(&mut self) {
+        if let Some(cur) = self.cur {
+            self.cur = self.list.node(cur).front;
+            if self.cur.is_some() {
+                *self.index.as_mut().unwrap() -= 1;
+            } else {
+                self.index = None;
+            }
+        } else if !self.list.is_empty() {
+            self.cur = self.list.back;
+            self.index = Some(self.list.len - 1);
+        }
+    }
+
+    pub fn current(&self) -> Option<&'a T> {
+        let list = self.list;
+        self.cur.map(|id| &list.node(id).elem)
+    }
+
+    pub fn peek_next(&self) -> Option<&'a T> {
+        let list = self.list;
+        let next = match self.cur {
+            Some(cur) => list.node(cur).back,
+            None => list.front,
+        };
+        next.map(|id| &list.node(id).elem)
+    }
+
+    pub fn peek_prev(&self) -> Option<&'a T> {
+        let list = self.list;
+        let prev = match self.cur {
+            Some(cur) => list.node(cur).front,
+            None => list.back,
+        };
+        prev.map(|id| &list.node(id).elem)
+    }
+
+    // 从当前位置往back方向迭代，包含当前元素
+    // 在ghost上时，ghost的下一个是front，所以迭代整个链表
+    pub fn iter_forward(&self) -> Iter<'a, T> {
+        match self.index {
+            Some(index) => Iter {
+                slots: &self.list.slots,
+                front: self.cur,
+                back: self.list.back,
+                len: self.list.len - index,
+            },
+            None => self.list.into_iter(),
+        }
+    }
+
+    // 从当前位置往front方向迭代，包含当前元素
+    // 在ghost上时，ghost的上一个是back，所以倒着迭代整个链表
+    pub fn iter_backward(&self) -> core::iter::Rev<Iter<'a, T>> {
+        match self.index {
+            Some(index) => Iter {
+                slots: &self.list.slots,
+                front: self.list.front,
+                back: self.cur,
+                len: index + 1,
+            }.rev(),
+            None => self.list.into_iter().rev(),
+        }
+    }
+}
+
+impl<'a, T> Clone for Cursor<'a, T> {
+    fn clone(&self) -> Self {
+        Cursor { ..*self }
+    }
+}
+// ================================================
+impl<T> Default for LinkedList<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// 按顺序一个个push，新链表的槽位是整理好的
+impl<T: Clone> Clone for LinkedList<T> {
+    fn clone(&self) -> Self {
+        let mut new_list = Self::with_capacity(self.len);
+        for item in self {
+            new_list.push_back(item.clone());
+        }
+        new_list
+    }
+}
+
+impl<T> Extend<T> for LinkedList<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for item in iter {
+            self.push_back(item);
+        }
+    }
+}
+
+impl<T> FromIterator<T> for LinkedList<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut list = Self::new();
+        list.extend(iter);
+        list
+    }
+}
+
+impl<T: Debug> Debug for LinkedList<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self).finish()
+    }
+}
+
+// 比较的是元素序列，槽位怎么排的不影响
+impl<T: PartialEq> PartialEq for LinkedList<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.into_iter().eq(other)
+    }
+}
+
+impl<T: Eq> Eq for LinkedList<T> {}
+
+impl<T: PartialOrd> PartialOrd for LinkedList<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.into_iter().partial_cmp(other)
+    }
+}
+
+impl<T: Ord> Ord for LinkedList<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.into_iter().cmp(other)
+    }
+}
+
+// 和sixth的一样，同样的元素哈希值也一样
+impl<T: Hash> Hash for LinkedList<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.len().hash(state);
+        for item in self {
+            item.hash(state);
+        }
+    }
+}
+
+// 没有裸指针，Send、Sync都是自动的
+
+// 通用接口 =======================================
+// 栈从front进出，队列从back进front出
+impl<T> crate::Stack for LinkedList<T> {
+    type Item = T;
+
+    fn push(&mut self, elem: T) {
+        self.push_front(elem)
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        self.pop_front()
+    }
+}
+
+impl<T> crate::Queue for LinkedList<T> {
+    type Item = T;
+
+    fn enqueue(&mut self, elem: T) {
+        self.push_back(elem)
+    }
+
+    fn dequeue(&mut self) -> Option<T> {
+        self.pop_front()
+    }
+}
+
+impl<T> crate::Deque for LinkedList<T> {
+    type Item = T;
+
+    fn push_front(&mut self, elem: T) {
+        self.push_front(elem)
+    }
+
+    fn push_back(&mut self, elem: T) {
+        self.push_back(elem)
+    }
+
+    fn pop_front(&mut self) -> Option<T> {
+        self.pop_front()
+    }
+
+    fn pop_back(&mut self) -> Option<T> {
+        self.pop_back()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ id_at, LinkedList, Slot };
+    use crate::test_util::{ to_vec, XorShift };
+    use std::collections::VecDeque;
+
+    crate::stack_conformance!(stack, super::LinkedList<i32>);
+    crate::queue_conformance!(queue, super::LinkedList<i32>);
+    crate::deque_conformance!(deque, super::LinkedList<i32>);
+
+    // 检查两个方向的链接、长度和空闲链表
+    fn check<T>(list: &LinkedList<T>) {
+        let mut prev = None;
+        let mut cur = list.front;
+        let mut len = 0;
+        while let Some(id) = cur {
+            assert_eq!(list.node(id).front, prev);
+            len += 1;
+            prev = cur;
+            cur = list.node(id).back;
+        }
+        assert_eq!(list.back, prev);
+        assert_eq!(list.len, len);
+
+        let mut free = 0;
+        let mut cur = list.free;
+        while let Some(id) = cur {
+            match list.slots[super::index(id)] {
+                Slot::Free(next) => cur = next,
+                Slot::Node(_) => panic!("free list points at a node"),
+            }
+            free += 1;
+        }
+        assert_eq!(len + free, list.slots.len());
+    }
+
+    // 按槽位顺序列出元素，空闲的槽位是None
+    fn slot_elems<T: Copy>(list: &LinkedList<T>) -> Vec<Option<T>> {
+        list.slots
+            .iter()
+            .map(|slot| match slot {
+                Slot::Node(node) => Some(node.elem),
+                Slot::Free(_) => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn basics() {
+        let mut list = LinkedList::new();
+        assert_eq!(list.pop_front(), None);
+        assert_eq!(list.pop_back(), None);
+        assert_eq!(list.front(), None);
+
+        list.push_back(1);
+        list.push_front(0);
+        list.push_back(2);
+        check(&list);
+        // 槽位按分配顺序排，front、back记的是槽位号
+        assert_eq!(slot_elems(&list), [Some(1), Some(0), Some(2)]);
+        assert_eq!((list.front, list.back), (Some(id_at(1)), Some(id_at(2))));
+        *list.front_mut().unwrap() = 10;
+        *list.back_mut().unwrap() = 20;
+        assert_eq!(to_vec(&list), [10, 1, 20]);
+
+        // 空闲链表后进先出：后还回来的槽位先被用上
+        assert_eq!(list.pop_back(), Some(20));
+        assert_eq!(list.pop_front(), Some(10));
+        check(&list);
+        assert_eq!(slot_elems(&list), [Some(1), None, None]);
+        assert_eq!(list.free, Some(id_at(1)));
+        list.push_back(3);
+        list.push_front(4);
+        check(&list);
+        assert_eq!(slot_elems(&list), [Some(1), Some(3), Some(4)]);
+        assert_eq!((list.front, list.back), (Some(id_at(2)), Some(id_at(1))));
+        assert_eq!(list.free, None);
+
+        // 最后一个元素拿走时清空整个数组，容量留着
+        assert_eq!(list.pop_front(), Some(4));
+        assert_eq!(list.pop_front(), Some(1));
+        assert_eq!(list.pop_front(), Some(3));
+        assert_eq!(list.pop_front(), None);
+        assert!(list.slots.is_empty());
+        assert_eq!(list.free, None);
+        assert!(list.capacity() >= 3);
+    }
+
+    #[test]
+    fn shrink_to_fit() {
+        // 两头交替插入再从中间删掉两个，槽位顺序和链表顺序对不上，还留着空洞
+        let mut list = LinkedList::new();
+        for i in 0..8 {
+            if i % 2 == 0 {
+                list.push_back(i);
+            } else {
+                list.push_front(i);
+            }
+        }
+        let mut cursor = list.cursor_at_mut(2);
+        cursor.remove_current();
+        cursor.remove_current();
+        check(&list);
+        assert_eq!(list.slots.len(), 8);
+        assert_eq!(to_vec(&list), [7, 5, 0, 2, 4, 6]);
+
+        list.shrink_to_fit();
+        check(&list);
+        assert_eq!(list.capacity(), 6);
+        assert_eq!(list.free, None);
+        // 第i个元素就在第i个槽位，前后链接是相邻的槽位
+        assert_eq!(slot_elems(&list), [Some(7), Some(5), Some(0), Some(2), Some(4), Some(6)]);
+        for i in 0..6 {
+            let node = list.node(id_at(i));
+            assert_eq!(node.front, i.checked_sub(1).map(id_at));
+            assert_eq!(node.back, (i + 1 < 6).then(|| id_at(i + 1)));
+        }
+
+        list.push_front(-1);
+        list.pop_back();
+        check(&list);
+        assert_eq!(to_vec(&list), [-1, 7, 5, 0, 2, 4]);
+    }
+
+    #[test]
+    fn iter() {
+        // 倒着push_front，槽位顺序和链表顺序正好相反；Iter只跟着链接走
+        let mut list = LinkedList::new();
+        for i in (0..6).rev() {
+            list.push_front(i);
+        }
+        assert_eq!(slot_elems(&list), [Some(5), Some(4), Some(3), Some(2), Some(1), Some(0)]);
+        let mut iter = (&list).into_iter();
+        assert_eq!(iter.len(), 6);
+        assert_eq!(iter.next(), Some(&0));
+        assert_eq!(iter.next_back(), Some(&5));
+        let rest = iter.clone();
+        assert_eq!(iter.by_ref().copied().collect::<Vec<_>>(), [1, 2, 3, 4]);
+        assert_eq!(iter.next_back(), None);
+        assert_eq!(rest.rev().copied().collect::<Vec<_>>(), [4, 3, 2, 1]);
+
+        // 打乱槽位顺序，iter_mut要先整理
+        list.push_front(-1);
+        list.pop_back();
+        list.push_back(6);
+        let mut cursor = list.cursor_at_mut(3);
+        cursor.remove_current();
+        cursor.insert_before(20);
+        for elem in &mut list {
+            *elem *= 10;
+        }
+        check(&list);
+        assert_eq!(list.free, None);
+        assert_eq!(list.slots.len(), list.len());
+        let mut iter = (&mut list).into_iter();
+        let front = iter.next().unwrap();
+        let back = iter.next_back().unwrap();
+        *front += 1;
+        *back += 1;
+        assert_eq!(iter.len(), 5);
+        assert_eq!(to_vec(&list), [-9, 0, 10, 200, 30, 40, 61]);
+
+        let mut iter = list.into_iter();
+        assert_eq!(iter.next_back(), Some(61));
+        assert_eq!(iter.next(), Some(-9));
+        assert_eq!(iter.len(), 5);
+        assert_eq!(iter.collect::<Vec<_>>(), [0, 10, 200, 30, 40]);
+
+        assert!((&mut LinkedList::<i32>::new()).into_iter().next().is_none());
+    }
+
+    #[test]
+    fn cursor_move_insert_remove() {
+        let mut list: LinkedList<i32> = (1..=6).collect();
+        let mut cursor = list.cursor_mut();
+        assert_eq!(cursor.current(), None);
+        assert_eq!(cursor.peek_next(), Some(&mut 1));
+        assert_eq!(cursor.peek_prev(), Some(&mut 6));
+        cursor.move_next();
+        cursor.move_next();
+        assert_eq!(cursor.current(), Some(&mut 2));
+        assert_eq!(cursor.index(), Some(1));
+        cursor.move_prev();
+        cursor.move_prev();
+        assert_eq!(cursor.index(), None);
+        cursor.move_prev();
+        assert_eq!(cursor.current(), Some(&mut 6));
+        assert_eq!(cursor.index(), Some(5));
+
+        cursor.seek(2);
+        assert_eq!(cursor.current(), Some(&mut 3));
+        cursor.move_by(-3);
+        assert_eq!(cursor.index(), None);
+        cursor.move_by(5);
+        assert_eq!(cursor.current(), Some(&mut 5));
+        assert_eq!(cursor.as_cursor().iter_forward().copied().collect::<Vec<_>>(), [5, 6]);
+        assert_eq!(cursor.as_cursor().iter_backward().copied().collect::<Vec<_>>(), [5, 4, 3, 2, 1]);
+
+        cursor.insert_before(40);
+        cursor.insert_after(50);
+        assert_eq!(cursor.index(), Some(5));
+        assert_eq!(cursor.replace_current(55), Ok(5));
+        assert_eq!(cursor.remove_current(), Some(55));
+        assert_eq!(cursor.current(), Some(&mut 50));
+        assert_eq!(cursor.remove_current(), Some(50));
+        assert_eq!(cursor.remove_current(), Some(6));
+        assert_eq!(cursor.index(), None);
+        assert_eq!(cursor.remove_current(), None);
+        assert_eq!(cursor.replace_current(0), Err(0));
+        cursor.insert_after(0);
+        cursor.insert_before(7);
+        let single = cursor.remove_current_as_list();
+        assert!(single.is_none());
+        cursor.move_prev();
+        let single = cursor.remove_current_as_list().unwrap();
+        assert_eq!(to_vec(&single), [7]);
+        check(&list);
+        assert_eq!(to_vec(&list), [0, 1, 2, 3, 4, 40]);
+
+        let cursor = list.cursor_back();
+        assert_eq!(cursor.current(), Some(&40));
+        assert_eq!(cursor.peek_prev(), Some(&4));
+        assert_eq!(cursor.peek_next(), None);
+        let mut other = cursor.clone();
+        other.move_next();
+        assert_eq!(other.index(), None);
+        assert_eq!(other.peek_next(), Some(&0));
+        assert_eq!(cursor.index(), Some(5));
+    }
+
+    #[test]
+    fn split_splice() {
+        let mut list: LinkedList<i32> = (0..10).collect();
+        // 前半段短，挪前半段
+        let mut cursor = list.cursor_at_mut(3);
+        let front = cursor.split_before();
+        assert_eq!(cursor.current(), Some(&mut 3));
+        assert_eq!(cursor.index(), Some(0));
+        // 后半段短，挪后半段
+        cursor.seek(4);
+        let back = cursor.split_after();
+        assert_eq!(cursor.current(), Some(&mut 7));
+        assert_eq!(cursor.index(), Some(4));
+        assert_eq!(to_vec(&front), [0, 1, 2]);
+        assert_eq!(to_vec(&back), [8, 9]);
+        assert_eq!(to_vec(&list), [3, 4, 5, 6, 7]);
+
+        // 反过来，被分出去的是长的一半
+        let mut cursor = list.cursor_at_mut(1);
+        let tail = cursor.split_after();
+        assert_eq!(cursor.current(), Some(&mut 4));
+        assert_eq!(to_vec(&tail), [5, 6, 7]);
+        cursor.splice_after(tail);
+        assert_eq!(cursor.current(), Some(&mut 4));
+        assert_eq!(cursor.index(), Some(1));
+        cursor.move_next();
+        let head = cursor.split_before();
+        assert_eq!(to_vec(&head), [3, 4]);
+        assert_eq!(cursor.current(), Some(&mut 5));
+        cursor.splice_before(head);
+        assert_eq!(cursor.index(), Some(2));
+        assert_eq!(cursor.current(), Some(&mut 5));
+
+        cursor.seek(5);
+        cursor.splice_after(front);
+        cursor.splice_before(back);
+        assert_eq!(cursor.index(), None);
+        check(&list);
+        assert_eq!(to_vec(&list), (0..10).collect::<Vec<_>>());
+
+        // ghost上split拿走整个链表
+        let mut cursor = list.cursor_mut();
+        let all = cursor.split_after();
+        assert!(list.is_empty());
+        let mut cursor = list.cursor_mut();
+        cursor.splice_before(all);
+        assert_eq!(cursor.index(), None);
+        assert_eq!(list.len(), 10);
+
+        let mut tail = list.split_off(7);
+        let mut head = list.split_off(0);
+        assert!(list.is_empty());
+        assert_eq!(to_vec(&head), (0..7).collect::<Vec<_>>());
+        head.append(&mut tail);
+        tail.push_back(-1);
+        head.prepend(&mut tail);
+        assert!(tail.is_empty());
+        check(&head);
+        assert_eq!(to_vec(&head), [-1, 0, 1, 2, 3, 4, 5, 6, 7, 8, 9]);
+        assert_eq!(head.split_off(11).len(), 0);
+    }
+
+    // 头尾进出、按位置插删、拆开再接上……随机混着来，每一步都和VecDeque比
+    #[test]
+    fn random_ops() {
+        let mut rng = XorShift(0x2545_f491_4f6c_dd1d);
+        let mut rand = |n| rng.below(n);
+
+        let mut list = LinkedList::new();
+        let mut model = VecDeque::new();
+        for i in 0..5000 {
+            match rand(11) {
+                0 => {
+                    list.push_front(i);
+                    model.push_front(i);
+                }
+                1 | 2 => {
+                    list.push_back(i);
+                    model.push_back(i);
+                }
+                3 => assert_eq!(list.pop_front(), model.pop_front()),
+                4 => assert_eq!(list.pop_back(), model.pop_back()),
+                5 => {
+                    let at = rand(model.len() + 1);
+                    let mut cursor = list.cursor_at_mut(at);
+                    cursor.insert_before(i);
+                    model.insert(at, i);
+                }
+                6 if !model.is_empty() => {
+                    let at = rand(model.len());
+                    assert_eq!(list.cursor_at_mut(at).remove_current(), model.remove(at));
+                }
+                7 => {
+                    let at = rand(model.len() + 1);
+                    let mut back = list.split_off(at);
+                    let mut model_back = model.split_off(at);
+                    back.push_front(i);
+                    model_back.push_front(i);
+                    list.append(&mut back);
+                    model.append(&mut model_back);
+                }
+                8 => {
+                    for elem in &mut list {
+                        *elem += 1;
+                    }
+                    for elem in &mut model {
+                        *elem += 1;
+                    }
+                }
+                9 => {
+                    list.sort_by_key(|x| x % 13);
+                    model.make_contiguous().sort_by_key(|x| x % 13);
+                }
+                _ => {
+                    model.retain(|&x| x % 7 != 0);
+                    list.retain(|&x| x % 7 != 0);
+                }
+            }
+            check(&list);
+            assert!((&list).into_iter().eq(&model));
+        }
+    }
+
+    #[test]
+    fn sort_and_extract() {
+        let mut list: LinkedList<(i32, i32)> = [(3, 0), (1, 1), (3, 2), (2, 3), (1, 4)].into_iter().collect();
+        list.pop_front();
+        list.push_front((3, 5));
+        list.sort_by_key(|&(key, _)| key);
+        check(&list);
+        // 稳定
+        assert_eq!(to_vec(&list), [(1, 1), (1, 4), (2, 3), (3, 5), (3, 2)]);
+        assert!(list.is_sorted_by(|a, b| a.0 <= b.0));
+
+        let mut list: LinkedList<i32> = (0..10).rev().collect();
+        list.sort();
+        assert_eq!(to_vec(&list), (0..10).collect::<Vec<_>>());
+        let odd: Vec<_> = list.extract_if(|x| *x % 2 == 1).collect();
+        assert_eq!(odd, [1, 3, 5, 7, 9]);
+        list.retain_mut(|x| {
+            *x *= 10;
+            *x != 40
+        });
+        check(&list);
+        assert_eq!(to_vec(&list), [0, 20, 60, 80]);
+
+        // 比较函数panic了，链表还是完整的
+        let mut list: LinkedList<i32> = (0..20).rev().collect();
+        let mut calls = 0;
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            list.sort_by(|a, b| {
+                calls += 1;
+                assert!(calls < 30);
+                a.cmp(b)
+            })
+        }));
+        assert!(result.is_err());
+        check(&list);
+        let mut elems = to_vec(&list);
+        elems.sort();
+        assert_eq!(elems, (0..20).collect::<Vec<_>>());
+
+        list.retain(|_| false);
+        assert!(list.is_empty());
+        assert!(list.slots.is_empty());
+    }
+
+    // 同一段代码，换个类型别名在sixth和arena上都跑一遍
+    #[test]
+    fn same_api_as_sixth() {
+        macro_rules! run {
+            ($list:ident) => {{
+                type List<T> = $list::LinkedList<T>;
+                let mut list: List<i32> = (0..8).collect();
+                list.push_front(-1);
+                let mut cursor = list.cursor_at_mut(4);
+                let mut tail = cursor.split_after();
+                cursor.insert_after(100);
+                tail.push_back(8);
+                cursor.move_prev();
+                cursor.splice_before(tail);
+                let removed: Vec<_> = list.extract_if(|x| *x % 3 == 0).collect();
+                for x in &mut list {
+                    *x += 1;
+                }
+                list.sort_by(|a, b| b.cmp(a));
+                let cursor = list.cursor_front();
+                let first = cursor.current().copied();
+                (list.into_iter().collect::<Vec<_>>(), removed, first)
+            }};
+        }
+        use crate::sixth;
+        assert_eq!(run!(sixth), run!(super));
+    }
+
+    #[test]
+    fn traits() {
+        let list: LinkedList<i32> = (0..4).collect();
+        assert_eq!(format!("{:?}", list), "[0, 1, 2, 3]");
+        assert_eq!(format!("{:?}", LinkedList::<i32>::default()), "[]");
+
+        // 槽位排得不一样也相等
+        let mut other = LinkedList::new();
+        for i in (0..4).rev() {
+            other.push_front(i);
+        }
+        assert_eq!(list, other);
+        assert_eq!(list.cmp(&other), std::cmp::Ordering::Equal);
+        other.pop_back();
+        assert!(other < list);
+        assert_eq!(list.clone(), list);
+
+        use std::hash::{ BuildHasher, RandomState };
+        let state = RandomState::new();
+        let sixth: crate::sixth::LinkedList<i32> = (0..4).collect();
+        assert_eq!(state.hash_one(&list), state.hash_one(&sixth));
+    }
+
+    #[test]
+    fn drops() {
+        use std::rc::Rc;
+
+        let counter = Rc::new(());
+        let mut list: LinkedList<_> = (0..10).map(|_| counter.clone()).collect();
+        drop(list.pop_front());
+        drop(list.pop_back());
+        assert_eq!(Rc::strong_count(&counter), 9);
+        let mut cursor = list.cursor_at_mut(3);
+        drop(cursor.remove_current());
+        drop(cursor.split_after());
+        assert_eq!(Rc::strong_count(&counter), 5);
+        let mut iter = list.into_iter();
+        iter.next();
+        drop(iter);
+        assert_eq!(Rc::strong_count(&counter), 1);
+    }
+
+    // 没有unsafe impl，全靠Vec和下标自动推出来
+    #[test]
+    fn send_sync() {
+        crate::assert_impl!(Send, Sync: LinkedList<i32>, super::Iter<i32>, super::IterMut<i32>, super::Cursor<i32>);
+        crate::assert_impl!(Send: super::IntoIter<i32>, super::CursorMut<i32>);
+    }
+
+    crate::assert_covariant!(_covariant: LinkedList, _covariant_iter: super::Iter<'i>, _covariant_into_iter: super::IntoIter);
+}
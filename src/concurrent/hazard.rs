@@ -0,0 +1,251 @@
+// Hazard pointer（风险指针）
+// 无锁结构里，一个线程把节点摘下来之后不能马上释放，因为别的线程可能刚读到这个指针还没来得及用
+// 解决办法：读的线程先把要用的指针“登记”在自己的hazard槽里，
+// 释放的线程把节点放进待回收列表（retire），攒够了再扫一遍所有hazard槽，没被登记的才真正释放
+//
+// 被登记的节点不会被释放，也就不会被重新分配出同一个地址，顺带解决了CAS的ABA问题
+//
+// 每个数据结构自己带一个Domain，结构drop时剩下的待回收节点一起释放，不会泄漏
+
+use alloc::{ boxed::Box, vec::Vec };
+use core::{
+    ptr,
+    sync::atomic::{ fence, AtomicBool, AtomicPtr, AtomicUsize, Ordering },
+};
+
+// 每个线程同时最多登记几个指针，MS队列出队要同时护住head和head.next
+pub(crate) const SLOTS: usize = 2;
+
+// 待回收的攒到这么多就扫一遍
+const SCAN_THRESHOLD: usize = 64;
+
+pub(crate) struct Domain {
+    // 只增不减的单链，线程用完记录就标记成不活跃，下次复用
+    records: AtomicPtr<Record>,
+    // 待回收的节点，只push，回收时整条swap出来，所以没有ABA
+    retired: AtomicPtr<Retired>,
+    retired_count: AtomicUsize,
+}
+
+struct Record {
+    active: AtomicBool,
+    hazards: [AtomicPtr<u8>; SLOTS],
+    next: *mut Record,
+}
+
+struct Retired {
+    ptr: *mut u8,
+    free: unsafe fn(*mut u8),
+    next: *mut Retired,
+}
+
+// 一次操作期间占着一条记录，drop时清空槽位并归还
+pub(crate) struct Guard<'d> {
+    domain: &'d Domain,
+    record: &'d Record,
+}
+
+impl Domain {
+    pub(crate) const fn new() -> Self {
+        Domain {
+            records: AtomicPtr::new(ptr::null_mut()),
+            retired: AtomicPtr::new(ptr::null_mut()),
+            retired_count: AtomicUsize::new(0),
+        }
+    }
+
+    pub(crate) fn guard(&self) -> Guard<'_> {
+        // 先找一条空闲的记录
+        let mut cur = self.records.load(Ordering::Acquire);
+        while !cur.is_null() {
+            let record = unsafe { &*cur };
+            if
+                !record.active.load(Ordering::Relaxed) &&
+                record.active
+                    .compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed)
+                    .is_ok()
+            {
+                return Guard { domain: self, record };
+            }
+            cur = record.next;
+        }
+
+        // 都在用，新建一条挂到开头
+        let new = Box::into_raw(
+            Box::new(Record {
+                active: AtomicBool::new(true),
+                hazards: [const { AtomicPtr::new(ptr::null_mut()) }; SLOTS],
+                next: ptr::null_mut(),
+            })
+        );
+        let mut head = self.records.load(Ordering::Relaxed);
+        loop {
+            unsafe {
+                (*new).next = head;
+            }
+            match
+                self.records.compare_exchange_weak(head, new, Ordering::AcqRel, Ordering::Relaxed)
+            {
+                Ok(_) => break,
+                Err(actual) => {
+                    head = actual;
+                }
+            }
+        }
+        Guard { domain: self, record: unsafe { &*new } }
+    }
+
+    // SAFETY: ptr已经从结构上摘下来了，之后不会再有新的线程读到它；free能正确释放ptr
+    unsafe fn retire(&self, ptr: *mut u8, free: unsafe fn(*mut u8)) {
+        let node = Box::into_raw(
+            Box::new(Retired {
+                ptr,
+                free,
+                next: ptr::null_mut(),
+            })
+        );
+        // 先计数再挂上去，否则别的线程的scan可能先把它释放并减掉计数，计数就下溢了
+        let count = self.retired_count.fetch_add(1, Ordering::Relaxed) + 1;
+        self.push_retired(node, node);
+        if count >= SCAN_THRESHOLD {
+            self.scan();
+        }
+    }
+
+    fn push_retired(&self, first: *mut Retired, last: *mut Retired) {
+        let mut head = self.retired.load(Ordering::Relaxed);
+        loop {
+            unsafe {
+                (*last).next = head;
+            }
+            match
+                self.retired.compare_exchange_weak(head, first, Ordering::Release, Ordering::Relaxed)
+            {
+                Ok(_) => {
+                    return;
+                }
+                Err(actual) => {
+                    head = actual;
+                }
+            }
+        }
+    }
+
+    // 释放所有没被登记的待回收节点
+    fn scan(&self) {
+        let mut list = self.retired.swap(ptr::null_mut(), Ordering::Acquire);
+        if list.is_null() {
+            return;
+        }
+
+        // 摘节点的CAS只是AcqRel，光靠下面的SeqCst load排不住"摘节点 → 读hazard槽"这个先后
+        // 所以先来一个SeqCst fence，和Guard::protect里"登记 → 再读一次"的SeqCst配对：
+        // 要么读者登记在先，这里能看到；要么摘节点在先，读者验证时会发现指针已经变了
+        fence(Ordering::SeqCst);
+        let mut hazards = Vec::new();
+        let mut cur = self.records.load(Ordering::SeqCst);
+        while !cur.is_null() {
+            let record = unsafe { &*cur };
+            for slot in &record.hazards {
+                let p = slot.load(Ordering::SeqCst);
+                if !p.is_null() {
+                    hazards.push(p);
+                }
+            }
+            cur = record.next;
+        }
+
+        let mut kept_first: *mut Retired = ptr::null_mut();
+        let mut kept_last: *mut Retired = ptr::null_mut();
+        let mut freed = 0;
+        while !list.is_null() {
+            let node = list;
+            unsafe {
+                list = (*node).next;
+                if hazards.contains(&(*node).ptr) {
+                    // 还有人在用，放回去下次再说
+                    (*node).next = kept_first;
+                    if kept_last.is_null() {
+                        kept_last = node;
+                    }
+                    kept_first = node;
+                } else {
+                    ((*node).free)((*node).ptr);
+                    drop(Box::from_raw(node));
+                    freed += 1;
+                }
+            }
+        }
+        self.retired_count.fetch_sub(freed, Ordering::Relaxed);
+        if !kept_first.is_null() {
+            self.push_retired(kept_first, kept_last);
+        }
+    }
+}
+
+impl Drop for Domain {
+    fn drop(&mut self) {
+        // &mut self，没有别的线程了，全部直接释放
+        unsafe {
+            let mut cur = *self.retired.get_mut();
+            while !cur.is_null() {
+                let node = Box::from_raw(cur);
+                (node.free)(node.ptr);
+                cur = node.next;
+            }
+            let mut cur = *self.records.get_mut();
+            while !cur.is_null() {
+                let record = Box::from_raw(cur);
+                cur = record.next;
+            }
+        }
+    }
+}
+
+impl<'d> Guard<'d> {
+    // 读出src并登记到slot，保证返回的指针在清掉登记之前不会被释放
+    // 登记完要再读一次确认没变，否则可能登记了一个已经摘下来的节点
+    pub(crate) fn protect<T>(&self, slot: usize, src: &AtomicPtr<T>) -> *mut T {
+        let mut p = src.load(Ordering::Relaxed);
+        loop {
+            self.record.hazards[slot].store(p as *mut u8, Ordering::SeqCst);
+            let again = src.load(Ordering::SeqCst);
+            if again == p {
+                return p;
+            }
+            p = again;
+        }
+    }
+
+    // 直接登记一个指针，调用者自己负责之后验证它还在结构里
+    pub(crate) fn set<T>(&self, slot: usize, p: *mut T) {
+        self.record.hazards[slot].store(p as *mut u8, Ordering::SeqCst);
+    }
+
+    // 清空所有登记，retire自己刚护住的节点之前先清掉，免得扫描时被自己拦下
+    pub(crate) fn clear(&self) {
+        for slot in &self.record.hazards {
+            slot.store(ptr::null_mut(), Ordering::Release);
+        }
+    }
+
+    // 节点摘下来后交给domain，等没人登记时用Box释放
+    // SAFETY: ptr是Box::into_raw来的，已经摘下来，并且只retire一次
+    pub(crate) unsafe fn retire<T>(&self, ptr: *mut T) {
+        unsafe fn free<T>(ptr: *mut u8) {
+            drop(Box::from_raw(ptr as *mut T));
+        }
+        self.domain.retire(ptr as *mut u8, free::<T>);
+    }
+}
+
+impl<'d> Drop for Guard<'d> {
+    fn drop(&mut self) {
+        self.clear();
+        self.record.active.store(false, Ordering::Release);
+    }
+}
+
+// Domain里只有原子指针，记录和待回收节点都是它自己管的
+unsafe impl Send for Domain {}
+unsafe impl Sync for Domain {}
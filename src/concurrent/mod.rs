@@ -0,0 +1,10 @@
+// 无锁并发的链表
+// 单线程版本见fifth（队列）、second（栈），这里是它们的多线程版本
+// 节点回收用的是自己实现的hazard pointer，不依赖外部crate
+
+mod hazard;
+pub mod queue;
+pub mod stack;
+
+pub use queue::AtomicQueue;
+pub use stack::AtomicStack;
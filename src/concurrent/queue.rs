@@ -0,0 +1,306 @@
+// Michael–Scott 无锁队列
+// 形状和fifth一样：head出、tail进，但多了一个哑节点（dummy）
+// head永远指向哑节点，真正的第一个元素在head.next里
+// 这样head和tail永远不为空，入队只改tail，出队只改head，两头互不干扰
+//
+//     head -> [dummy] -> [1] -> [2] -> [3] <- tail
+//
+// 出队时把head挪到[1]，[1]里的值拿走，[1]变成新的哑节点，旧的dummy交给hazard pointer回收
+
+use alloc::boxed::Box;
+use core::{
+    mem::MaybeUninit,
+    ptr,
+    sync::atomic::{ AtomicPtr, Ordering },
+};
+
+use super::hazard::Domain;
+
+pub struct AtomicQueue<T> {
+    head: AtomicPtr<Node<T>>,
+    tail: AtomicPtr<Node<T>>,
+    domain: Domain,
+}
+
+struct Node<T> {
+    // 哑节点的elem是未初始化的
+    elem: MaybeUninit<T>,
+    next: AtomicPtr<Node<T>>,
+}
+
+impl<T> Node<T> {
+    fn new(elem: MaybeUninit<T>) -> *mut Self {
+        Box::into_raw(
+            Box::new(Node {
+                elem,
+                next: AtomicPtr::new(ptr::null_mut()),
+            })
+        )
+    }
+}
+
+impl<T> AtomicQueue<T> {
+    pub fn new() -> Self {
+        let dummy = Node::new(MaybeUninit::uninit());
+        AtomicQueue {
+            head: AtomicPtr::new(dummy),
+            tail: AtomicPtr::new(dummy),
+            domain: Domain::new(),
+        }
+    }
+
+    // 从尾部入
+    pub fn push(&self, elem: T) {
+        let new = Node::new(MaybeUninit::new(elem));
+        let guard = self.domain.guard();
+        loop {
+            let tail = guard.protect(0, &self.tail);
+            let next = unsafe { (*tail).next.load(Ordering::Acquire) };
+            if !next.is_null() {
+                // tail落后了（别人挂上了节点但还没挪tail），帮它挪一下再重试
+                let _ = self.tail.compare_exchange(tail, next, Ordering::Release, Ordering::Relaxed);
+                continue;
+            }
+            let linked = unsafe {
+                (*tail).next.compare_exchange(
+                    ptr::null_mut(),
+                    new,
+                    Ordering::Release,
+                    Ordering::Relaxed
+                )
+            };
+            if linked.is_ok() {
+                // 挪tail失败也没关系，说明别人已经帮忙挪过了
+                let _ = self.tail.compare_exchange(tail, new, Ordering::Release, Ordering::Relaxed);
+                return;
+            }
+        }
+    }
+
+    // 从头部出
+    pub fn pop(&self) -> Option<T> {
+        let guard = self.domain.guard();
+        loop {
+            let head = guard.protect(0, &self.head);
+            let next = unsafe { (*head).next.load(Ordering::Acquire) };
+            if next.is_null() {
+                return None;
+            }
+            // 登记next后确认head没变：head没变说明next还没被出队，更不可能被回收
+            guard.set(1, next);
+            if self.head.load(Ordering::SeqCst) != head {
+                continue;
+            }
+
+            let tail = self.tail.load(Ordering::Acquire);
+            if head == tail {
+                // tail落后了，帮忙挪，否则head会跑到tail前面去
+                let _ = self.tail.compare_exchange(tail, next, Ordering::Release, Ordering::Relaxed);
+                continue;
+            }
+
+            if
+                self.head
+                    .compare_exchange(head, next, Ordering::AcqRel, Ordering::Relaxed)
+                    .is_ok()
+            {
+                // 只有CAS成功的线程能拿走next里的值，next从此变成哑节点
+                let elem = unsafe { (*next).elem.assume_init_read() };
+                guard.clear();
+                unsafe {
+                    guard.retire(head);
+                }
+                return Some(elem);
+            }
+        }
+    }
+
+    // 只是某一瞬间的快照，返回时可能已经变了
+    pub fn is_empty(&self) -> bool {
+        let guard = self.domain.guard();
+        let head = guard.protect(0, &self.head);
+        unsafe { (*head).next.load(Ordering::Acquire).is_null() }
+    }
+}
+
+impl<T> Default for AtomicQueue<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Drop for AtomicQueue<T> {
+    fn drop(&mut self) {
+        // &mut self，没有别的线程了，直接走一遍
+        unsafe {
+            let dummy = Box::from_raw(*self.head.get_mut());
+            let mut cur = dummy.next.load(Ordering::Relaxed);
+            while !cur.is_null() {
+                let mut node = Box::from_raw(cur);
+                node.elem.assume_init_drop();
+                cur = node.next.load(Ordering::Relaxed);
+            }
+        }
+        // 剩下已经retire的节点由domain的drop释放
+    }
+}
+
+// 值在线程间转移，所以只要T: Send
+// 没有任何&T能被多个线程同时拿到，所以Sync也只要T: Send
+unsafe impl<T: Send> Send for AtomicQueue<T> {}
+unsafe impl<T: Send> Sync for AtomicQueue<T> {}
+
+impl<T> crate::Queue for AtomicQueue<T> {
+    type Item = T;
+
+    fn enqueue(&mut self, elem: T) {
+        self.push(elem)
+    }
+
+    fn dequeue(&mut self) -> Option<T> {
+        self.pop()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::AtomicQueue;
+    use std::sync::{ atomic::{ AtomicUsize, Ordering }, Arc };
+    #[cfg(feature = "std")]
+    use std::thread;
+
+    crate::queue_conformance!(queue, super::AtomicQueue<i32>);
+
+    #[test]
+    fn basics() {
+        let queue = AtomicQueue::new();
+        assert!(queue.is_empty());
+        assert_eq!(queue.pop(), None);
+
+        queue.push(1);
+        queue.push(2);
+        assert!(!queue.is_empty());
+        assert_eq!(queue.pop(), Some(1));
+        queue.push(3);
+        assert_eq!(queue.pop(), Some(2));
+        assert_eq!(queue.pop(), Some(3));
+        assert_eq!(queue.pop(), None);
+        assert!(queue.is_empty());
+    }
+
+    // 数drop次数，检查不丢不重也不漏释放
+    struct Counted(Arc<AtomicUsize>);
+
+    impl Drop for Counted {
+        fn drop(&mut self) {
+            self.0.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    #[test]
+    fn drop_remaining() {
+        let drops = Arc::new(AtomicUsize::new(0));
+        let queue = AtomicQueue::new();
+        for _ in 0..10 {
+            queue.push(Counted(drops.clone()));
+        }
+        for _ in 0..4 {
+            drop(queue.pop());
+        }
+        assert_eq!(drops.load(Ordering::Relaxed), 4);
+        drop(queue);
+        assert_eq!(drops.load(Ordering::Relaxed), 10);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn mpmc_stress() {
+        const PRODUCERS: usize = 4;
+        const CONSUMERS: usize = 4;
+        const PER_PRODUCER: usize = 20_000;
+
+        let queue = Arc::new(AtomicQueue::new());
+        let popped = Arc::new(AtomicUsize::new(0));
+
+        let producers: Vec<_> = (0..PRODUCERS)
+            .map(|p| {
+                let queue = queue.clone();
+                thread::spawn(move || {
+                    for i in 0..PER_PRODUCER {
+                        queue.push((p, i));
+                    }
+                })
+            })
+            .collect();
+
+        let consumers: Vec<_> = (0..CONSUMERS)
+            .map(|_| {
+                let queue = queue.clone();
+                let popped = popped.clone();
+                thread::spawn(move || {
+                    let mut got = Vec::new();
+                    while popped.load(Ordering::Relaxed) < PRODUCERS * PER_PRODUCER {
+                        if let Some(item) = queue.pop() {
+                            popped.fetch_add(1, Ordering::Relaxed);
+                            got.push(item);
+                        } else {
+                            thread::yield_now();
+                        }
+                    }
+                    got
+                })
+            })
+            .collect();
+
+        for producer in producers {
+            producer.join().unwrap();
+        }
+        let mut all = Vec::new();
+        for consumer in consumers {
+            let got = consumer.join().unwrap();
+            // 同一个生产者的元素，在同一个消费者那里应该是按顺序的
+            for p in 0..PRODUCERS {
+                let seq: Vec<_> = got
+                    .iter()
+                    .filter(|&&(from, _)| from == p)
+                    .map(|&(_, i)| i)
+                    .collect();
+                assert!(seq.windows(2).all(|w| w[0] < w[1]));
+            }
+            all.extend(got);
+        }
+
+        // 不丢不重
+        assert_eq!(all.len(), PRODUCERS * PER_PRODUCER);
+        all.sort();
+        all.dedup();
+        assert_eq!(all.len(), PRODUCERS * PER_PRODUCER);
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn stress_drop_count() {
+        let drops = Arc::new(AtomicUsize::new(0));
+        let queue = Arc::new(AtomicQueue::new());
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let queue = queue.clone();
+                let drops = drops.clone();
+                thread::spawn(move || {
+                    for i in 0..5_000 {
+                        queue.push(Counted(drops.clone()));
+                        if i % 3 != 0 {
+                            drop(queue.pop());
+                        }
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        drop(queue);
+        assert_eq!(drops.load(Ordering::Relaxed), 4 * 5_000);
+    }
+}
@@ -0,0 +1,318 @@
+// Treiber 无锁栈
+// 形状和second一样：只有一个head，push/pop都在头部
+// 区别是head换成了AtomicPtr，所有修改都是一次CAS
+//
+// pop的ABA问题：线程A读到head=X、next=Y，还没CAS，
+// 别的线程把X、Y都pop掉又push了一个恰好复用X地址的新节点，A的CAS就会成功并把已释放的Y挂回去
+// 这里X被A登记在hazard里，不会被释放，地址也就不会被复用，ABA不会发生
+
+use alloc::boxed::Box;
+use core::{
+    mem::ManuallyDrop,
+    ptr,
+    sync::atomic::{ AtomicPtr, Ordering },
+};
+
+use super::hazard::Domain;
+use crate::second;
+
+pub struct AtomicStack<T> {
+    head: AtomicPtr<Node<T>>,
+    domain: Domain,
+}
+
+struct Node<T> {
+    // 值由pop拿走，节点本身交给domain回收，所以不能让Box的drop再drop一次值
+    elem: ManuallyDrop<T>,
+    // 发布之后不再修改
+    next: *mut Node<T>,
+}
+
+impl<T> AtomicStack<T> {
+    pub fn new() -> Self {
+        AtomicStack {
+            head: AtomicPtr::new(ptr::null_mut()),
+            domain: Domain::new(),
+        }
+    }
+
+    pub fn push(&self, elem: T) {
+        let new = Box::into_raw(
+            Box::new(Node {
+                elem: ManuallyDrop::new(elem),
+                next: ptr::null_mut(),
+            })
+        );
+        // push不读别人的节点，不需要hazard
+        self.link(new, new);
+    }
+
+    // 按迭代顺序依次push，但整串只用一次CAS挂上去，中间不会插进别的线程的元素
+    pub fn push_all<I: IntoIterator<Item = T>>(&self, iter: I) {
+        let mut top: *mut Node<T> = ptr::null_mut();
+        let mut bottom: *mut Node<T> = ptr::null_mut();
+        for elem in iter {
+            top = Box::into_raw(
+                Box::new(Node {
+                    elem: ManuallyDrop::new(elem),
+                    next: top,
+                })
+            );
+            if bottom.is_null() {
+                bottom = top;
+            }
+        }
+        if !top.is_null() {
+            self.link(top, bottom);
+        }
+    }
+
+    // 把还没发布的top..=bottom这一串挂到栈顶
+    fn link(&self, top: *mut Node<T>, bottom: *mut Node<T>) {
+        let mut head = self.head.load(Ordering::Relaxed);
+        loop {
+            // bottom还没发布，随便改
+            unsafe {
+                (*bottom).next = head;
+            }
+            match self.head.compare_exchange_weak(head, top, Ordering::Release, Ordering::Relaxed) {
+                Ok(_) => {
+                    return;
+                }
+                Err(actual) => {
+                    head = actual;
+                }
+            }
+        }
+    }
+
+    pub fn pop(&self) -> Option<T> {
+        let guard = self.domain.guard();
+        loop {
+            let head = guard.protect(0, &self.head);
+            if head.is_null() {
+                return None;
+            }
+            let next = unsafe { (*head).next };
+            if
+                self.head
+                    .compare_exchange(head, next, Ordering::AcqRel, Ordering::Relaxed)
+                    .is_ok()
+            {
+                let elem = unsafe { ManuallyDrop::take(&mut (*head).elem) };
+                guard.clear();
+                unsafe {
+                    guard.retire(head);
+                }
+                return Some(elem);
+            }
+        }
+    }
+
+    // 一次swap拿走整条链，转成普通的单线程栈，栈顶还是栈顶
+    // 别的线程可能还登记着这些节点（比如CAS失败前的pop），节点只能retire，不能改成second的节点接着用
+    // 所以值要搬进second新分配的节点里，每个元素一次分配，O(n)
+    pub fn take_all(&self) -> second::List<T> {
+        let mut cur = self.head.swap(ptr::null_mut(), Ordering::Acquire);
+        if cur.is_null() {
+            return second::List::new();
+        }
+
+        let guard = self.domain.guard();
+        second::List::from_top_down(
+            core::iter::from_fn(|| {
+                if cur.is_null() {
+                    return None;
+                }
+                unsafe {
+                    let next = (*cur).next;
+                    let elem = ManuallyDrop::take(&mut (*cur).elem);
+                    guard.retire(cur);
+                    cur = next;
+                    Some(elem)
+                }
+            })
+        )
+    }
+
+    // 只是某一瞬间的快照，返回时可能已经变了
+    pub fn is_empty(&self) -> bool {
+        self.head.load(Ordering::Acquire).is_null()
+    }
+}
+
+impl<T> Default for AtomicStack<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Drop for AtomicStack<T> {
+    fn drop(&mut self) {
+        // &mut self，没有别的线程了，直接走一遍
+        let mut cur = *self.head.get_mut();
+        while !cur.is_null() {
+            unsafe {
+                let mut node = Box::from_raw(cur);
+                ManuallyDrop::drop(&mut node.elem);
+                cur = node.next;
+            }
+        }
+    }
+}
+
+// 和AtomicQueue一样，值只会被转移，不会被共享
+unsafe impl<T: Send> Send for AtomicStack<T> {}
+unsafe impl<T: Send> Sync for AtomicStack<T> {}
+
+impl<T> crate::Stack for AtomicStack<T> {
+    type Item = T;
+
+    fn push(&mut self, elem: T) {
+        AtomicStack::push(self, elem)
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        AtomicStack::pop(self)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::AtomicStack;
+    use std::sync::{ atomic::{ AtomicUsize, Ordering }, Arc };
+    #[cfg(feature = "std")]
+    use std::thread;
+
+    crate::stack_conformance!(stack, super::AtomicStack<i32>);
+
+    #[test]
+    fn basics() {
+        let stack = AtomicStack::new();
+        assert!(stack.is_empty());
+        assert_eq!(stack.pop(), None);
+
+        stack.push(1);
+        stack.push(2);
+        stack.push(3);
+        assert!(!stack.is_empty());
+        assert_eq!(stack.pop(), Some(3));
+        assert_eq!(stack.pop(), Some(2));
+        stack.push(4);
+        assert_eq!(stack.pop(), Some(4));
+        assert_eq!(stack.pop(), Some(1));
+        assert_eq!(stack.pop(), None);
+    }
+
+    #[test]
+    fn push_all_take_all() {
+        let stack = AtomicStack::new();
+        stack.push(0);
+        stack.push_all(1..4);
+        stack.push_all(std::iter::empty());
+        assert_eq!(stack.pop(), Some(3));
+
+        let mut list = stack.take_all();
+        assert!(stack.is_empty());
+        assert_eq!(list.peek(), Some(&2));
+        assert_eq!(list.pop(), Some(2));
+        assert_eq!(list.pop(), Some(1));
+        assert_eq!(list.pop(), Some(0));
+        assert_eq!(list.pop(), None);
+
+        assert_eq!(stack.take_all().peek(), None);
+    }
+
+    struct Counted(Arc<AtomicUsize>);
+
+    impl Drop for Counted {
+        fn drop(&mut self) {
+            self.0.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    #[test]
+    fn drop_remaining() {
+        let drops = Arc::new(AtomicUsize::new(0));
+        let stack = AtomicStack::new();
+        for _ in 0..10 {
+            stack.push(Counted(drops.clone()));
+        }
+        drop(stack.pop());
+        drop(stack.take_all());
+        assert_eq!(drops.load(Ordering::Relaxed), 10);
+
+        stack.push_all((0..5).map(|_| Counted(drops.clone())));
+        drop(stack);
+        assert_eq!(drops.load(Ordering::Relaxed), 15);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn stress() {
+        const THREADS: usize = 8;
+        const PER_THREAD: usize = 20_000;
+
+        let stack = Arc::new(AtomicStack::new());
+        // 每个线程push、pop交替，还夹杂着push_all和take_all，最后所有值都要恰好出现一次
+        let handles: Vec<_> = (0..THREADS)
+            .map(|t| {
+                let stack = stack.clone();
+                thread::spawn(move || {
+                    let mut got = Vec::new();
+                    for i in 0..PER_THREAD {
+                        let v = t * PER_THREAD + i;
+                        if i % 100 == 0 {
+                            got.extend(stack.take_all());
+                        }
+                        if i % 10 == 0 {
+                            stack.push_all([v]);
+                        } else {
+                            stack.push(v);
+                        }
+                        if i % 2 == 0 {
+                            got.extend(stack.pop());
+                        }
+                    }
+                    got
+                })
+            })
+            .collect();
+
+        let mut all: Vec<_> = handles
+            .into_iter()
+            .flat_map(|h| h.join().unwrap())
+            .collect();
+        all.extend(stack.take_all());
+        assert!(stack.is_empty());
+
+        all.sort();
+        assert_eq!(all, (0..THREADS * PER_THREAD).collect::<Vec<_>>());
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn stress_drop_count() {
+        let drops = Arc::new(AtomicUsize::new(0));
+        let stack = Arc::new(AtomicStack::new());
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let stack = stack.clone();
+                let drops = drops.clone();
+                thread::spawn(move || {
+                    for i in 0..5_000 {
+                        stack.push(Counted(drops.clone()));
+                        if i % 3 != 0 {
+                            drop(stack.pop());
+                        }
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        drop(stack);
+        assert_eq!(drops.load(Ordering::Relaxed), 4 * 5_000);
+    }
+}
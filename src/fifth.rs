@@ -3,11 +3,26 @@
 // 裸指针不需要指定生命周期
 
 // 对于异步实现，可以用std::pin钉住内存中的某个数据
-use std::ptr;
-
+use alloc::boxed::Box;
+use core::{
+    cmp::Ordering,
+    fmt::{ self, Debug },
+    hash::{ Hash, Hasher },
+    marker::PhantomData,
+    ptr::NonNull,
+};
+
+// 最早的版本：
+// pub struct List<T> {
+//     head: Link<T>,
+//     tail: *mut Node<T>, // 裸指针声明不是unsafe的，所以不需要包裹
+// }
+// 现在和sixth一样改成NonNull + PhantomData，见下面Link的说明
 pub struct List<T> {
     head: Link<T>,
-    tail: *mut Node<T>, // 裸指针声明不是unsafe的，所以不需要包裹
+    tail: Link<T>,
+    len: usize,
+    _boo: PhantomData<T>,
 }
 
 // 为什么要用裸指针？
@@ -61,7 +76,11 @@ pub fn pop(&'a mut self) -> Option<T> {
 
 // 后面可以知道这种写法是invariant的，代表所连接的节点生命周期和指针一样
 // 改成covariant的，可以让所连接节点生命周期大于指针
-type Link<T> = *mut Node<T>;
+// type Link<T> = *mut Node<T>;
+
+// 学完sixth回来改的：NonNull里是*const，所以是协变的
+// 裸指针不表示拥有T，用PhantomData<T>告诉编译器（drop检查、Send/Sync推导）List拥有T
+type Link<T> = Option<NonNull<Node<T>>>;
 
 struct Node<T> {
     elem: T,
@@ -71,19 +90,30 @@ struct Node<T> {
 pub struct IntoIter<T>(List<T>);
 
 // 不改成裸指针，因为会丢掉生命周期标识？否则需要用到PhantomData，PhantomData是什么？
+// pub struct Iter<'a, T> {
+//     next: Option<&'a Node<T>>,
+// }
+// 答：PhantomData<&'a T>就是用来补上生命周期标识的，和sixth的Iter一样
+// 再带上剩余个数len，size_hint就是准确的
 pub struct Iter<'a, T> {
-    next: Option<&'a Node<T>>,
+    next: Link<T>,
+    len: usize,
+    _boo: PhantomData<&'a T>,
 }
 
 pub struct IterMut<'a, T> {
-    next: Option<&'a mut Node<T>>,
+    next: Link<T>,
+    len: usize,
+    _boo: PhantomData<&'a mut T>,
 }
 
 impl<T> List<T> {
     pub fn new() -> Self {
         List {
-            head: ptr::null_mut(),
-            tail: ptr::null_mut(), // 裸指针可为null
+            head: None,
+            tail: None,
+            len: 0,
+            _boo: PhantomData,
         }
     }
 
@@ -91,22 +121,27 @@ impl<T> List<T> {
     pub fn push(&mut self, elem: T) {
         // 创建就转成裸指针了
         // Box本身会被吃掉，但会生成一个裸指针，内部指向的数据也不会释放
-        let new_tail = Box::into_raw(
-            Box::new(Node {
-                elem,
-                next: ptr::null_mut(),
-            })
-        );
-
-        if !self.tail.is_null() {
+        let new_tail = unsafe {
+            NonNull::new_unchecked(
+                Box::into_raw(
+                    Box::new(Node {
+                        elem,
+                        next: None,
+                    })
+                )
+            )
+        };
+
+        if let Some(old_tail) = self.tail {
             unsafe {
-                (*self.tail).next = new_tail;
+                (*old_tail.as_ptr()).next = Some(new_tail);
             }
         } else {
-            self.head = new_tail;
+            self.head = Some(new_tail);
         }
 
-        self.tail = new_tail;
+        self.tail = Some(new_tail);
+        self.len += 1;
     }
 
     // pub fn push(&'a mut self, elem: T) {
@@ -137,23 +172,30 @@ impl<T> List<T> {
     // }
 
     pub fn pop(&mut self) -> Option<T> {
-        if self.head.is_null() {
-            None
-        } else {
+        self.head.map(|head| {
             // Box会重新获得self.head的所有权，之后一定要脱钩，让self.head指向新对象
-            let head = unsafe { Box::from_raw(self.head) };
+            let head = unsafe { Box::from_raw(head.as_ptr()) };
             self.head = head.next;
 
-            if self.head.is_null() {
-                self.tail = ptr::null_mut();
+            if self.head.is_none() {
+                self.tail = None;
             }
 
-            Some(head.elem)
-        }
+            self.len -= 1;
+            head.elem
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
     }
 
     pub fn peek(&self) -> Option<&T> {
-        unsafe { self.head.as_ref().map(|node| &node.elem) }
+        unsafe { self.head.map(|node| &(*node.as_ptr()).elem) }
     }
 
     // 借用检查呢？
@@ -166,13 +208,19 @@ impl<T> List<T> {
     // 用借用栈来解释就是，self先压栈，返回值后压栈
     // 再调用peek_mut，将再创建一个新&mut self，使老&mut self及之上都弹栈
     pub fn peek_mut(&mut self) -> Option<&mut T> {
-        unsafe { self.head.as_mut().map(|node| &mut node.elem) }
+        unsafe { self.head.map(|node| &mut (*node.as_ptr()).elem) }
+    }
+}
+
+impl<T> Default for List<T> {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
 impl<T> Drop for List<T> {
     fn drop(&mut self) {
-        while let Some(_) = self.pop() {}
+        while self.pop().is_some() {}
     }
 }
 
@@ -194,10 +242,10 @@ impl<'a, T> IntoIterator for &'a List<T> {
     type IntoIter = Iter<'a, T>;
 
     fn into_iter(self) -> Self::IntoIter {
-        unsafe {
-            Iter {
-                next: self.head.as_ref(),
-            }
+        Iter {
+            next: self.head,
+            len: self.len,
+            _boo: PhantomData,
         }
     }
 }
@@ -208,10 +256,10 @@ impl<'a, T> IntoIterator for &'a mut List<T> {
     type IntoIter = IterMut<'a, T>;
 
     fn into_iter(self) -> Self::IntoIter {
-        unsafe {
-            IterMut {
-                next: self.head.as_mut(),
-            }
+        IterMut {
+            next: self.head,
+            len: self.len,
+            _boo: PhantomData,
         }
     }
 }
@@ -223,33 +271,152 @@ impl<T> Iterator for IntoIter<T> {
     fn next(&mut self) -> Option<Self::Item> {
         self.0.pop()
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.0.len, Some(self.0.len))
+    }
+}
+
+impl<T> ExactSizeIterator for IntoIter<T> {
+    fn len(&self) -> usize {
+        self.0.len
+    }
 }
 
 impl<'a, T> Iterator for Iter<'a, T> {
     type Item = &'a T;
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.next.map(|node| {
-            self.next = unsafe { node.next.as_ref() };
-            &node.elem
+        self.next.map(|node| unsafe {
+            self.len -= 1;
+            self.next = (*node.as_ptr()).next;
+            &(*node.as_ptr()).elem
         })
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<'a, T> ExactSizeIterator for Iter<'a, T> {
+    fn len(&self) -> usize {
+        self.len
+    }
 }
 
 impl<'a, T> Iterator for IterMut<'a, T> {
     type Item = &'a mut T;
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.next.take().map(|node| {
-            self.next = unsafe { node.next.as_mut() };
-            &mut node.elem
+        self.next.map(|node| unsafe {
+            self.len -= 1;
+            self.next = (*node.as_ptr()).next;
+            &mut (*node.as_ptr()).elem
         })
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<'a, T> ExactSizeIterator for IterMut<'a, T> {
+    fn len(&self) -> usize {
+        self.len
+    }
+}
+
+//====================================================================
+// 和sixth一样的一套trait，单链只能从头往后走，所以没有DoubleEndedIterator
+impl<T: Clone> Clone for List<T> {
+    fn clone(&self) -> Self {
+        self.into_iter().cloned().collect()
+    }
+}
+
+impl<T> Extend<T> for List<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for item in iter {
+            self.push(item);
+        }
+    }
+}
+
+impl<T> FromIterator<T> for List<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut list = Self::new();
+        list.extend(iter);
+        list
+    }
+}
+
+impl<T: Debug> Debug for List<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self).finish()
+    }
+}
+
+impl<T: PartialEq> PartialEq for List<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.into_iter().eq(other)
+    }
+}
+
+impl<T: Eq> Eq for List<T> {}
+
+impl<T: PartialOrd> PartialOrd for List<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.into_iter().partial_cmp(other)
+    }
+}
+
+impl<T: Ord> Ord for List<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.into_iter().cmp(other)
+    }
+}
+
+impl<T: Hash> Hash for List<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.len().hash(state);
+        for item in self {
+            item.hash(state);
+        }
+    }
+}
+
+// 裸指针让编译器推导不出Send、Sync，要自己写
+// List拥有T，和Box<T>一样：T能Send就能Send，T能Sync就能Sync
+// Iter只给&T，所以要T: Sync；IterMut给&mut T，和&mut T的规则一样
+unsafe impl<T: Send> Send for List<T> {}
+unsafe impl<T: Sync> Sync for List<T> {}
+
+unsafe impl<'a, T: Sync> Send for Iter<'a, T> {}
+unsafe impl<'a, T: Sync> Sync for Iter<'a, T> {}
+
+unsafe impl<'a, T: Send> Send for IterMut<'a, T> {}
+unsafe impl<'a, T: Sync> Sync for IterMut<'a, T> {}
+
+//====================================================================
+impl<T> crate::Queue for List<T> {
+    type Item = T;
+
+    fn enqueue(&mut self, elem: T) {
+        self.push(elem)
+    }
+
+    fn dequeue(&mut self) -> Option<T> {
+        self.pop()
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::List;
+
+    crate::queue_conformance!(queue, super::List<i32>);
+
     #[test]
     fn basics() {
         let mut list = List::new();
@@ -346,9 +513,9 @@ mod test {
 
         assert!(list.peek() == Some(&3));
         list.push(6);
-        list.peek_mut().map(|x| {
+        if let Some(x) = list.peek_mut() {
             *x *= 10;
-        });
+        }
         assert!(list.peek() == Some(&30));
         assert!(list.pop() == Some(30));
 
@@ -364,15 +531,93 @@ mod test {
         assert_eq!(iter.next(), None);
 
         assert!(list.pop() == Some(400));
-        list.peek_mut().map(|x| {
+        if let Some(x) = list.peek_mut() {
             *x *= 10;
-        });
+        }
         assert!(list.peek() == Some(&5000));
         list.push(7);
 
         // Drop it on the ground and let the dtor exercise itself
     }
 
+    #[test]
+    fn len_size_hint() {
+        let mut list: List<i32> = (1..=3).collect();
+        assert_eq!(list.len(), 3);
+        assert!(!list.is_empty());
+
+        let mut iter = (&list).into_iter();
+        assert_eq!(iter.size_hint(), (3, Some(3)));
+        iter.next();
+        assert_eq!(iter.len(), 2);
+
+        let mut iter = (&mut list).into_iter();
+        iter.next();
+        iter.next();
+        assert_eq!(iter.size_hint(), (1, Some(1)));
+        iter.next();
+        assert_eq!(iter.size_hint(), (0, Some(0)));
+        assert_eq!(iter.next(), None);
+
+        assert_eq!(list.pop(), Some(1));
+        assert_eq!(list.len(), 2);
+        let mut iter = list.into_iter();
+        assert_eq!(iter.size_hint(), (2, Some(2)));
+        iter.next();
+        assert_eq!(iter.len(), 1);
+
+        let mut list = List::default();
+        assert_eq!(list.len(), 0);
+        assert!(list.is_empty());
+        list.push(1);
+        list.pop();
+        assert!(list.is_empty());
+    }
+
+    #[test]
+    fn traits() {
+        let mut list: List<i32> = (0..5).collect();
+        list.extend(5..10);
+        assert_eq!(format!("{:?}", list), "[0, 1, 2, 3, 4, 5, 6, 7, 8, 9]");
+
+        let cloned = list.clone();
+        assert_eq!(cloned, list);
+        assert_eq!(cloned.len(), 10);
+        list.push(10);
+        assert_ne!(cloned, list);
+        assert!(cloned < list);
+        assert_eq!(cloned.cmp(&list), std::cmp::Ordering::Less);
+
+        let a: List<i32> = (0..3).collect();
+        let b: List<i32> = (1..4).collect();
+        assert!(a < b);
+        assert!(b > a);
+
+        let nan: List<f64> = [f64::NAN].into_iter().collect();
+        assert_eq!(nan.partial_cmp(&nan), None);
+
+        let mut map = std::collections::HashMap::new();
+        assert_eq!(map.insert(a.clone(), "a"), None);
+        assert_eq!(map.insert(b.clone(), "b"), None);
+        assert_eq!(map.get(&a), Some(&"a"));
+        assert_eq!(map.remove(&b), Some("b"));
+    }
+
+    #[test]
+    fn send_sync() {
+        crate::assert_impl!(Send, Sync: List<i32>, super::Iter<i32>, super::IterMut<i32>);
+        crate::assert_impl!(Send: super::IntoIter<i32>);
+
+        #[cfg(feature = "std")]
+        {
+            let list: List<i32> = (0..100).collect();
+            let sum = std::thread::spawn(move || (&list).into_iter().sum::<i32>()).join().unwrap();
+            assert_eq!(sum, 4950);
+        }
+    }
+
+    crate::assert_covariant!(_covariant: List, _covariant_iter: super::Iter<'i>);
+
     // borrow test =====================================================
     // 认识借用栈
     // rust用借用栈来处理再借用
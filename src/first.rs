@@ -1,7 +1,8 @@
 // 1 A Bad Stack
 // 方便理解但别扭的实现
 
-use std::mem;
+use alloc::boxed::Box;
+use core::mem;
 
 pub struct List {
     head: Link,
@@ -24,7 +25,7 @@ impl List {
 
     pub fn push(&mut self, elem: i32) {
         let new_node = Box::new(Node {
-            elem: elem,
+            elem,
             // Empty会赋值到self.head里，而self.head里原来的值会赋值到next里
             next: mem::replace(&mut self.head, Link::Empty),
         });
@@ -43,6 +44,12 @@ impl List {
     }
 }
 
+impl Default for List {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Drop for List {
     fn drop(&mut self) {
         let mut cur_link = mem::replace(&mut self.head, Link::Empty);
@@ -53,10 +60,24 @@ impl Drop for List {
     }
 }
 
+impl crate::Stack for List {
+    type Item = i32;
+
+    fn push(&mut self, elem: i32) {
+        self.push(elem)
+    }
+
+    fn pop(&mut self) -> Option<i32> {
+        self.pop()
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::List;
 
+    crate::stack_conformance!(stack, super::List);
+
     #[test]
     fn basics() {
         let mut list = List::new();
@@ -27,8 +27,9 @@
 // 但为什么不直接用&、&mut？
 // 因为Ref和RefMut为RefCell提供占用状态
 // Ref与RefMut不在编译时与正主绑定生命周期
-use std::cell::{ Ref, RefCell, RefMut };
-use std::rc::Rc;
+use alloc::rc::{ Rc, Weak };
+use core::cell::{ BorrowError, BorrowMutError, Ref, RefCell, RefMut };
+use core::marker::PhantomData;
 
 pub struct List<T> {
     head: Link<T>,
@@ -37,14 +38,45 @@ pub struct List<T> {
 
 type Link<T> = Option<Rc<RefCell<Node<T>>>>;
 
+// 两头都用Rc的话相邻节点互相持有，成了引用环，谁都释放不了
+// 往回指的换成Weak：只有head -> next -> ... -> tail这一条强引用链，链表一丢整条链跟着释放
+type WeakLink<T> = Option<Weak<RefCell<Node<T>>>>;
+
 struct Node<T> {
     elem: T,
     next: Link<T>,
-    prev: Link<T>,
+    prev: WeakLink<T>,
 }
 
 pub struct IntoIter<T>(List<T>);
 
+// 借用迭代器产出的是Ref/RefMut而不是&/&mut
+// 每个元素在产出时才borrow，产出的守卫一直持有到调用者把它drop掉
+//
+// 借用冲突：某个节点已经被借走（比如手上还留着它的RefMut）时再借它，
+// iter/iter_mut会像RefCell一样panic，try_iter/try_iter_mut则产出Err，并且之后不再产出任何东西
+// （借不到节点就读不到它的next/prev，没法往下走）
+// 目前拿到RefMut都要&mut List，和迭代器的借用互斥，所以正常用法下不会冲突
+pub struct Iter<'a, T>(TryIter<'a, T>);
+
+pub struct IterMut<'a, T>(TryIterMut<'a, T>);
+
+pub struct TryIter<'a, T> {
+    ends: Ends<T>,
+    _boo: PhantomData<&'a List<T>>,
+}
+
+pub struct TryIterMut<'a, T> {
+    ends: Ends<T>,
+    _boo: PhantomData<&'a mut List<T>>,
+}
+
+// 两头各拿着下一个要产出的节点的Rc，相遇后两头一起清空
+struct Ends<T> {
+    front: Link<T>,
+    back: Link<T>,
+}
+
 impl<T> Node<T> {
     fn new(elem: T) -> Rc<RefCell<Self>> {
         Rc::new(
@@ -69,7 +101,7 @@ impl<T> List<T> {
         let new_head = Node::new(elem);
         match self.head.take() {
             Some(old_head) => {
-                old_head.borrow_mut().prev = Some(new_head.clone());
+                old_head.borrow_mut().prev = Some(Rc::downgrade(&new_head));
                 new_head.borrow_mut().next = Some(old_head);
                 self.head = Some(new_head);
             }
@@ -95,7 +127,7 @@ impl<T> List<T> {
         })
     }
 
-    pub fn peek_front(&self) -> Option<Ref<T>> {
+    pub fn peek_front(&self) -> Option<Ref<'_, T>> {
         self.head.as_ref().map(|node| Ref::map(node.borrow(), |node| &node.elem))
     }
 
@@ -104,7 +136,7 @@ impl<T> List<T> {
         match self.tail.take() {
             Some(old_tail) => {
                 old_tail.borrow_mut().next = Some(new_tail.clone());
-                new_tail.borrow_mut().prev = Some(old_tail);
+                new_tail.borrow_mut().prev = Some(Rc::downgrade(&old_tail));
                 self.tail = Some(new_tail);
             }
             None => {
@@ -116,7 +148,8 @@ impl<T> List<T> {
 
     pub fn pop_back(&mut self) -> Option<T> {
         self.tail.take().map(|old_tail| {
-            match old_tail.borrow_mut().prev.take() {
+            // 前一个节点被head那条强引用链拿着，一定还活着
+            match old_tail.borrow_mut().prev.take().and_then(|prev| prev.upgrade()) {
                 Some(new_tail) => {
                     new_tail.borrow_mut().next.take();
                     self.tail = Some(new_tail);
@@ -129,17 +162,39 @@ impl<T> List<T> {
         })
     }
 
-    pub fn peek_back(&self) -> Option<Ref<T>> {
+    pub fn peek_back(&self) -> Option<Ref<'_, T>> {
         self.tail.as_ref().map(|node| Ref::map(node.borrow(), |node| &node.elem))
     }
 
-    pub fn peek_back_mut(&mut self) -> Option<RefMut<T>> {
+    pub fn peek_back_mut(&mut self) -> Option<RefMut<'_, T>> {
         self.tail.as_ref().map(|node| RefMut::map(node.borrow_mut(), |node| &mut node.elem))
     }
 
-    pub fn peek_front_mut(&mut self) -> Option<RefMut<T>> {
+    pub fn peek_front_mut(&mut self) -> Option<RefMut<'_, T>> {
         self.head.as_ref().map(|node| RefMut::map(node.borrow_mut(), |node| &mut node.elem))
     }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter(self.try_iter())
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut(self.try_iter_mut())
+    }
+
+    pub fn try_iter(&self) -> TryIter<'_, T> {
+        TryIter {
+            ends: Ends::new(self),
+            _boo: PhantomData,
+        }
+    }
+
+    pub fn try_iter_mut(&mut self) -> TryIterMut<'_, T> {
+        TryIterMut {
+            ends: Ends::new(self),
+            _boo: PhantomData,
+        }
+    }
 }
 
 impl<T> IntoIterator for List<T> {
@@ -165,9 +220,228 @@ impl<T> DoubleEndedIterator for IntoIter<T> {
     }
 }
 
+impl<'a, T> IntoIterator for &'a List<T> {
+    type Item = Ref<'a, T>;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut List<T> {
+    type Item = RefMut<'a, T>;
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+impl<T> Ends<T> {
+    fn new(list: &List<T>) -> Self {
+        Ends {
+            front: list.head.clone(),
+            back: list.tail.clone(),
+        }
+    }
+
+    fn pop_front(&mut self) -> Link<T> {
+        let node = self.front.take()?;
+        if self.back.as_ref().is_some_and(|back| Rc::ptr_eq(back, &node)) {
+            self.back = None;
+        }
+        Some(node)
+    }
+
+    fn pop_back(&mut self) -> Link<T> {
+        let node = self.back.take()?;
+        if self.front.as_ref().is_some_and(|front| Rc::ptr_eq(front, &node)) {
+            self.front = None;
+        }
+        Some(node)
+    }
+
+    fn stop(&mut self) {
+        self.front = None;
+        self.back = None;
+    }
+
+    // Ref/RefMut要借&'a RefCell，但手上只有局部的Rc
+    // SAFETY: 迭代器借着整个链表（'a），借期内没有任何API能把节点摘下来，
+    // 链表自己的Rc保证节点活得比'a久，迭代器里的Rc只是让相邻的节点多一份保险
+    unsafe fn cell<'a>(node: &Rc<RefCell<Node<T>>>) -> &'a RefCell<Node<T>> {
+        &*Rc::as_ptr(node)
+    }
+}
+
+impl<'a, T> Iterator for TryIter<'a, T> {
+    type Item = Result<Ref<'a, T>, BorrowError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.ends.pop_front()?;
+        match unsafe { Ends::cell(&node) }.try_borrow() {
+            Ok(node) => {
+                if self.ends.back.is_some() {
+                    self.ends.front = node.next.clone();
+                }
+                Some(Ok(Ref::map(node, |node| &node.elem)))
+            }
+            Err(err) => {
+                self.ends.stop();
+                Some(Err(err))
+            }
+        }
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for TryIter<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let node = self.ends.pop_back()?;
+        match unsafe { Ends::cell(&node) }.try_borrow() {
+            Ok(node) => {
+                if self.ends.front.is_some() {
+                    self.ends.back = node.prev.as_ref().and_then(Weak::upgrade);
+                }
+                Some(Ok(Ref::map(node, |node| &node.elem)))
+            }
+            Err(err) => {
+                self.ends.stop();
+                Some(Err(err))
+            }
+        }
+    }
+}
+
+impl<'a, T> Iterator for TryIterMut<'a, T> {
+    type Item = Result<RefMut<'a, T>, BorrowMutError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.ends.pop_front()?;
+        match unsafe { Ends::cell(&node) }.try_borrow_mut() {
+            Ok(node) => {
+                // 产出之前就把next读出来，之后不用再借这个节点
+                // 另一头已经清空说明两头相遇了，不用再往下走
+                if self.ends.back.is_some() {
+                    self.ends.front = node.next.clone();
+                }
+                Some(Ok(RefMut::map(node, |node| &mut node.elem)))
+            }
+            Err(err) => {
+                self.ends.stop();
+                Some(Err(err))
+            }
+        }
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for TryIterMut<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let node = self.ends.pop_back()?;
+        match unsafe { Ends::cell(&node) }.try_borrow_mut() {
+            Ok(node) => {
+                if self.ends.front.is_some() {
+                    self.ends.back = node.prev.as_ref().and_then(Weak::upgrade);
+                }
+                Some(Ok(RefMut::map(node, |node| &mut node.elem)))
+            }
+            Err(err) => {
+                self.ends.stop();
+                Some(Err(err))
+            }
+        }
+    }
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = Ref<'a, T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(|elem| elem.expect("already mutably borrowed"))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for Iter<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.0.next_back().map(|elem| elem.expect("already mutably borrowed"))
+    }
+}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = RefMut<'a, T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(|elem| elem.expect("already borrowed"))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for IterMut<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.0.next_back().map(|elem| elem.expect("already borrowed"))
+    }
+}
+
+impl<T> Default for List<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// 没有环了，但直接drop head还是会沿着next递归，长链表会爆栈
+// 一个个断开next就行，不用像pop_front那样把元素搬出来再维护两头
 impl<T> Drop for List<T> {
     fn drop(&mut self) {
-        while self.pop_front().is_some() {}
+        self.tail.take();
+        let mut cur = self.head.take();
+        while let Some(node) = cur {
+            cur = node.borrow_mut().next.take();
+        }
+    }
+}
+
+// 通用接口 =========================================
+// 栈从front进出，队列从back进front出
+impl<T> crate::Stack for List<T> {
+    type Item = T;
+
+    fn push(&mut self, elem: T) {
+        self.push_front(elem)
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        self.pop_front()
+    }
+}
+
+impl<T> crate::Queue for List<T> {
+    type Item = T;
+
+    fn enqueue(&mut self, elem: T) {
+        self.push_back(elem)
+    }
+
+    fn dequeue(&mut self) -> Option<T> {
+        self.pop_front()
+    }
+}
+
+impl<T> crate::Deque for List<T> {
+    type Item = T;
+
+    fn push_front(&mut self, elem: T) {
+        self.push_front(elem)
+    }
+
+    fn push_back(&mut self, elem: T) {
+        self.push_back(elem)
+    }
+
+    fn pop_front(&mut self) -> Option<T> {
+        self.pop_front()
+    }
+
+    fn pop_back(&mut self) -> Option<T> {
+        self.pop_back()
     }
 }
 
@@ -175,6 +449,10 @@ impl<T> Drop for List<T> {
 mod test {
     use super::List;
 
+    crate::stack_conformance!(stack, super::List<i32>);
+    crate::queue_conformance!(queue, super::List<i32>);
+    crate::deque_conformance!(deque, super::List<i32>);
+
     #[test]
     fn basics() {
         let mut list = List::new();
@@ -262,4 +540,178 @@ mod test {
         assert_eq!(iter.next_back(), None);
         assert_eq!(iter.next(), None);
     }
+
+    #[test]
+    fn iter() {
+        let mut list = List::new();
+        list.push_back(1);
+        list.push_back(2);
+        list.push_back(3);
+
+        let mut iter = list.iter();
+        assert_eq!(*iter.next().unwrap(), 1);
+        assert_eq!(*iter.next_back().unwrap(), 3);
+        assert_eq!(*iter.next().unwrap(), 2);
+        assert!(iter.next_back().is_none());
+        assert!(iter.next().is_none());
+
+        // 多个Ref可以同时拿着
+        let all: Vec<_> = list.iter().collect();
+        assert_eq!(all.iter().map(|elem| **elem).collect::<Vec<_>>(), [1, 2, 3]);
+        let rev: Vec<_> = list.iter().rev().map(|elem| *elem).collect();
+        assert_eq!(rev, [3, 2, 1]);
+        drop(all);
+
+        assert!(List::<i32>::new().iter().next().is_none());
+    }
+
+    #[test]
+    fn iter_mut() {
+        let mut list = List::new();
+        list.push_back(1);
+        list.push_back(2);
+        list.push_back(3);
+        list.push_back(4);
+
+        for mut elem in &mut list {
+            *elem *= 10;
+        }
+
+        // 两头的RefMut同时拿着也不冲突
+        let mut iter = list.iter_mut();
+        let mut front = iter.next().unwrap();
+        let mut back = iter.next_back().unwrap();
+        *front += 1;
+        *back += 1;
+        let mut middle: Vec<_> = iter.collect();
+        assert_eq!(middle.len(), 2);
+        *middle[0] += 2;
+        drop((front, back, middle));
+
+        let all: Vec<_> = list.iter().map(|elem| *elem).collect();
+        assert_eq!(all, [11, 22, 30, 41]);
+    }
+
+    #[test]
+    fn try_iter() {
+        let mut list = List::new();
+        list.push_back(1);
+        list.push_back(2);
+
+        let all: Result<Vec<_>, _> = list.try_iter().collect();
+        assert_eq!(all.unwrap().len(), 2);
+        assert!(list.try_iter_mut().all(|elem| elem.is_ok()));
+
+        // 正常API下拿不到冲突的借用，这里直接借住节点来模拟
+        let head = list.head.clone().unwrap();
+        let held = head.borrow_mut();
+
+        let mut iter = list.try_iter();
+        assert!(iter.next().unwrap().is_err());
+        assert!(iter.next().is_none());
+        assert!(iter.next_back().is_none());
+
+        // 从另一头能走到被占用的节点为止
+        let mut iter = list.try_iter();
+        assert_eq!(*iter.next_back().unwrap().unwrap(), 2);
+        assert!(iter.next_back().unwrap().is_err());
+
+        drop(held);
+        let held = head.borrow();
+        let mut iter = list.try_iter();
+        assert_eq!(*iter.next().unwrap().unwrap(), 1);
+        drop(iter);
+        let mut iter = list.try_iter_mut();
+        assert!(iter.next().unwrap().is_err());
+        assert!(iter.next().is_none());
+        drop(held);
+    }
+
+    #[test]
+    #[should_panic(expected = "already mutably borrowed")]
+    fn iter_conflict_panics() {
+        let mut list = List::new();
+        list.push_back(1);
+        let head = list.head.clone().unwrap();
+        let _held = head.borrow_mut();
+        list.iter().next();
+    }
+
+    // 沿着next把每个节点的Weak都收集起来，用来检查最后是否都释放了
+    fn node_handles(list: &List<i32>) -> Vec<super::Weak<super::RefCell<super::Node<i32>>>> {
+        let mut handles = Vec::new();
+        let mut cur = list.head.clone();
+        while let Some(node) = cur {
+            handles.push(super::Rc::downgrade(&node));
+            cur = node.borrow().next.clone();
+        }
+        handles
+    }
+
+    #[test]
+    fn ref_counts() {
+        use super::Rc;
+
+        let mut list = List::new();
+        for i in 0..4 {
+            list.push_back(i);
+        }
+        list.push_front(-1);
+
+        // 强引用只有head -> next -> ... 这一条链，再加上list.tail
+        // 弱引用来自后一个节点的prev
+        let mut cur = list.head.clone();
+        let mut index = 0;
+        while let Some(node) = cur {
+            let is_tail = node.borrow().next.is_none();
+            // 减去这里的cur自己
+            let strong = Rc::strong_count(&node) - 1;
+            assert_eq!(strong, if is_tail { 2 } else { 1 }, "node {index}");
+            assert_eq!(Rc::weak_count(&node), if is_tail { 0 } else { 1 }, "node {index}");
+            cur = node.borrow().next.clone();
+            index += 1;
+        }
+        assert_eq!(index, 5);
+    }
+
+    #[test]
+    fn no_leaks() {
+        let mut list = List::new();
+        for i in 0..10 {
+            list.push_back(i);
+        }
+        let handles = node_handles(&list);
+        drop(list);
+        assert!(handles.iter().all(|node| node.upgrade().is_none()));
+
+        // pop出来的节点也要释放干净
+        let mut list = List::new();
+        for i in 0..10 {
+            list.push_front(i);
+        }
+        let handles = node_handles(&list);
+        list.pop_front();
+        list.pop_back();
+        assert!(handles[0].upgrade().is_none());
+        assert!(handles[9].upgrade().is_none());
+        assert_eq!(handles.iter().filter(|node| node.upgrade().is_some()).count(), 8);
+
+        // 迭代器手里的Rc也不会留下什么
+        {
+            let mut iter = list.iter();
+            iter.next();
+            iter.next_back();
+        }
+        while list.pop_back().is_some() {}
+        assert!(handles.iter().all(|node| node.upgrade().is_none()));
+    }
+
+    #[test]
+    fn long_drop() {
+        let mut list = List::new();
+        for i in 0..100_000 {
+            list.push_back(i);
+        }
+        drop(list);
+    }
 }
@@ -0,0 +1,651 @@
+// 侵入式双向链表
+// 前面几章的链表都自己拥有节点：Box<Node<T>>、Rc<RefCell<Node<T>>>、NonNull<Node<T>>
+// 元素被包在节点里，想同时放进好几个链表就只能复制或者再包一层Rc
+//
+// 侵入式反过来：链接（Link）嵌在用户自己的结构体里，链表只是把这些Link串起来
+// 一个结构体里放几个Link，就能同时待在几个链表里，插入删除都不分配内存
+//
+//     struct Task {
+//         id: u32,
+//         run: Link,   // 在就绪队列里的位置
+//         all: Link,   // 在全部任务列表里的位置
+//     }
+//
+// 链表不拥有元素，只借用：元素用Pin<&'a T>传进来，链表活多久元素就得活多久，期间也不能被移动
+// 因为Link里存的是别的Link的地址，元素一挪地址就悬垂了，所以Link是!Unpin的
+//
+// 从Link找回所在的结构体靠Adapter，一般用intrusive_adapter!生成
+
+use core::{
+    cell::Cell,
+    fmt::{ self, Debug },
+    marker::{ PhantomData, PhantomPinned },
+    pin::Pin,
+    ptr::NonNull,
+};
+
+// 嵌在元素里的链接，和sixth的Node一样用front/back指前后
+// 元素只能以&T的形式被借出去，所以用Cell改
+pub struct Link {
+    front: Cell<Option<NonNull<Link>>>,
+    back: Cell<Option<NonNull<Link>>>,
+    // 是否已经在某个链表里
+    // 重复插入（同一个链表两次，或者两个链表）会把两边的链接都搞乱，链表又是safe接口，所以release下也要查
+    linked: Cell<bool>,
+    _pin: PhantomPinned,
+}
+
+impl Link {
+    pub const fn new() -> Self {
+        Link {
+            front: Cell::new(None),
+            back: Cell::new(None),
+            linked: Cell::new(false),
+            _pin: PhantomPinned,
+        }
+    }
+
+    fn mark_linked(&self) {
+        assert!(!self.linked.replace(true), "Link is already in a list");
+    }
+
+    fn mark_unlinked(&self) {
+        self.linked.set(false);
+    }
+}
+
+impl Default for Link {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Debug for Link {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Link").finish_non_exhaustive()
+    }
+}
+
+/// 描述Link嵌在Value的哪个字段里，链表靠它在Link和Value之间来回换算。
+///
+/// # Safety
+///
+/// `link`必须返回`value`内部某个固定字段的地址，`from_link`必须是它的逆运算。
+/// 同一个Value里的不同Link要用不同的Adapter。
+pub unsafe trait Adapter {
+    type Value;
+
+    /// # Safety
+    ///
+    /// `value`必须指向一个活着的Value。
+    unsafe fn link(value: NonNull<Self::Value>) -> NonNull<Link>;
+
+    /// # Safety
+    ///
+    /// `link`必须是由`Self::link`得到的。
+    unsafe fn from_link(link: NonNull<Link>) -> NonNull<Self::Value>;
+}
+
+// 生成一个Adapter：intrusive_adapter!(pub RunAdapter = Task { run });
+// 指针都是从整个Value的指针投影出来的，再减回去不会越出原来的出处（provenance）
+#[macro_export]
+macro_rules! intrusive_adapter {
+    ($vis:vis $name:ident = $value:ty { $field:ident }) => {
+        $vis struct $name;
+
+        unsafe impl $crate::intrusive::Adapter for $name {
+            type Value = $value;
+
+            unsafe fn link(
+                value: ::core::ptr::NonNull<$value>
+            ) -> ::core::ptr::NonNull<$crate::intrusive::Link> {
+                unsafe {
+                    ::core::ptr::NonNull::new_unchecked(
+                        ::core::ptr::addr_of_mut!((*value.as_ptr()).$field)
+                    )
+                }
+            }
+
+            unsafe fn from_link(
+                link: ::core::ptr::NonNull<$crate::intrusive::Link>
+            ) -> ::core::ptr::NonNull<$value> {
+                unsafe { link.byte_sub(::core::mem::offset_of!($value, $field)).cast() }
+            }
+        }
+    };
+}
+
+pub struct List<'a, A: Adapter> {
+    front: Option<NonNull<Link>>,
+    back: Option<NonNull<Link>>,
+    len: usize,
+    // 借用了一堆&'a Value
+    _boo: PhantomData<&'a A::Value>,
+}
+
+pub struct Iter<'l, 'a, A: Adapter> {
+    front: Option<NonNull<Link>>,
+    back: Option<NonNull<Link>>,
+    len: usize,
+    _boo: PhantomData<&'l List<'a, A>>,
+}
+
+// 和sixth::CursorMut一样，cur为None时停在ghost上
+pub struct CursorMut<'l, 'a, A: Adapter> {
+    cur: Option<NonNull<Link>>,
+    list: &'l mut List<'a, A>,
+    index: Option<usize>,
+}
+
+impl<'a, A: Adapter> List<'a, A> {
+    pub fn new() -> Self {
+        List {
+            front: None,
+            back: None,
+            len: 0,
+            _boo: PhantomData,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn front(&self) -> Option<Pin<&'a A::Value>> {
+        self.front.map(|link| unsafe { Self::value(link) })
+    }
+
+    pub fn back(&self) -> Option<Pin<&'a A::Value>> {
+        self.back.map(|link| unsafe { Self::value(link) })
+    }
+
+    pub fn push_front(&mut self, value: Pin<&'a A::Value>) {
+        unsafe {
+            let link = Self::link(value);
+            self.link_between(link, None, self.front);
+        }
+    }
+
+    pub fn push_back(&mut self, value: Pin<&'a A::Value>) {
+        unsafe {
+            let link = Self::link(value);
+            self.link_between(link, self.back, None);
+        }
+    }
+
+    pub fn pop_front(&mut self) -> Option<Pin<&'a A::Value>> {
+        self.front.map(|link| unsafe {
+            self.unlink(link);
+            Self::value(link)
+        })
+    }
+
+    pub fn pop_back(&mut self) -> Option<Pin<&'a A::Value>> {
+        self.back.map(|link| unsafe {
+            self.unlink(link);
+            Self::value(link)
+        })
+    }
+
+    // 元素不归链表管，清空只是把链接都拆掉
+    pub fn clear(&mut self) {
+        while self.pop_front().is_some() {}
+    }
+
+    pub fn iter(&self) -> Iter<'_, 'a, A> {
+        Iter {
+            front: self.front,
+            back: self.back,
+            len: self.len,
+            _boo: PhantomData,
+        }
+    }
+
+    pub fn cursor_mut(&mut self) -> CursorMut<'_, 'a, A> {
+        CursorMut {
+            cur: None,
+            list: self,
+            index: None,
+        }
+    }
+
+    pub fn cursor_front_mut(&mut self) -> CursorMut<'_, 'a, A> {
+        CursorMut {
+            cur: self.front,
+            index: self.front.map(|_| 0),
+            list: self,
+        }
+    }
+
+    pub fn cursor_back_mut(&mut self) -> CursorMut<'_, 'a, A> {
+        CursorMut {
+            cur: self.back,
+            index: self.back.map(|_| self.len - 1),
+            list: self,
+        }
+    }
+
+    unsafe fn link(value: Pin<&'a A::Value>) -> NonNull<Link> {
+        A::link(NonNull::from(value.get_ref()))
+    }
+
+    // SAFETY: link属于这个链表，对应的Value至少活到'a，并且一直被Pin着
+    unsafe fn value(link: NonNull<Link>) -> Pin<&'a A::Value> {
+        Pin::new_unchecked(A::from_link(link).as_ref())
+    }
+
+    // 把还没进任何链表的new接到front和back中间，两者必须在这个链表里相邻（None表示链表那头）
+    unsafe fn link_between(
+        &mut self,
+        new: NonNull<Link>,
+        front: Option<NonNull<Link>>,
+        back: Option<NonNull<Link>>
+    ) {
+        let link = new.as_ref();
+        link.mark_linked();
+        link.front.set(front);
+        link.back.set(back);
+        match front {
+            Some(front) => front.as_ref().back.set(Some(new)),
+            None => {
+                self.front = Some(new);
+            }
+        }
+        match back {
+            Some(back) => back.as_ref().front.set(Some(new)),
+            None => {
+                self.back = Some(new);
+            }
+        }
+        self.len += 1;
+    }
+
+    // 把link从这个链表摘下来，并清空它的前后指针
+    unsafe fn unlink(&mut self, link: NonNull<Link>) {
+        let node = link.as_ref();
+        let front = node.front.replace(None);
+        let back = node.back.replace(None);
+        match front {
+            Some(front) => front.as_ref().back.set(back),
+            None => {
+                self.front = back;
+            }
+        }
+        match back {
+            Some(back) => back.as_ref().front.set(front),
+            None => {
+                self.back = front;
+            }
+        }
+        node.mark_unlinked();
+        self.len -= 1;
+    }
+}
+
+impl<'a, A: Adapter> Default for List<'a, A> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// 链表没了，元素还在，得把它们的链接拆掉，不然元素里留着悬垂的指针，也没法再插入
+// 链表被mem::forget的话拆不了，元素一直标着linked，之后再插入会panic，不会去用那些悬垂的指针
+impl<'a, A: Adapter> Drop for List<'a, A> {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+impl<'a, A: Adapter> Debug for List<'a, A> where A::Value: Debug {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<'l, 'a, A: Adapter> IntoIterator for &'l List<'a, A> {
+    type Item = Pin<&'a A::Value>;
+    type IntoIter = Iter<'l, 'a, A>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'l, 'a, A: Adapter> Iterator for Iter<'l, 'a, A> {
+    type Item = Pin<&'a A::Value>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        self.front.map(|link| unsafe {
+            self.len -= 1;
+            self.front = link.as_ref().back.get();
+            List::<A>::value(link)
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<'l, 'a, A: Adapter> DoubleEndedIterator for Iter<'l, 'a, A> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        self.back.map(|link| unsafe {
+            self.len -= 1;
+            self.back = link.as_ref().front.get();
+            List::<A>::value(link)
+        })
+    }
+}
+
+impl<'l, 'a, A: Adapter> ExactSizeIterator for Iter<'l, 'a, A> {
+    fn len(&self) -> usize {
+        self.len
+    }
+}
+
+// 光标 ===========================================
+// 和sixth::CursorMut一样的用法，只是元素是借来的，插入的是Pin<&'a Value>，移除的也原样还回去
+impl<'l, 'a, A: Adapter> CursorMut<'l, 'a, A> {
+    pub fn index(&self) -> Option<usize> {
+        self.index
+    }
+
+    pub fn move_next(&mut self) {
+        if let Some(cur) = self.cur {
+            self.cur = unsafe { cur.as_ref().back.get() };
+            self.index = match self.cur {
+                Some(_) => self.index.map(|i| i + 1),
+                // 走到ghost上了
+                None => None,
+            };
+        } else if !self.list.is_empty() {
+            self.cur = self.list.front;
+            self.index = Some(0);
+        }
+    }
+
+    pub fn move_prev(&mut self) {
+        if let Some(cur) = self.cur {
+            self.cur = unsafe { cur.as_ref().front.get() };
+            self.index = match self.cur {
+                Some(_) => self.index.map(|i| i - 1),
+                // 走到ghost上了
+                None => None,
+            };
+        } else if !self.list.is_empty() {
+            self.cur = self.list.back;
+            self.index = Some(self.list.len - 1);
+        }
+    }
+
+    pub fn current(&self) -> Option<Pin<&'a A::Value>> {
+        self.cur.map(|link| unsafe { List::<A>::value(link) })
+    }
+
+    pub fn peek_next(&self) -> Option<Pin<&'a A::Value>> {
+        let next = match self.cur {
+            Some(cur) => unsafe { cur.as_ref().back.get() },
+            None => self.list.front,
+        };
+        next.map(|link| unsafe { List::<A>::value(link) })
+    }
+
+    pub fn peek_prev(&self) -> Option<Pin<&'a A::Value>> {
+        let prev = match self.cur {
+            Some(cur) => unsafe { cur.as_ref().front.get() },
+            None => self.list.back,
+        };
+        prev.map(|link| unsafe { List::<A>::value(link) })
+    }
+
+    // 在当前元素后插入，ghost上则插到front
+    // index不变
+    pub fn insert_after(&mut self, value: Pin<&'a A::Value>) {
+        unsafe {
+            let new = List::<A>::link(value);
+            match self.cur {
+                Some(cur) => self.list.link_between(new, Some(cur), cur.as_ref().back.get()),
+                None => self.list.link_between(new, None, self.list.front),
+            }
+        }
+    }
+
+    // 在当前元素前插入，ghost上则插到back
+    // 当前元素往后挪了一位，所以index加1
+    pub fn insert_before(&mut self, value: Pin<&'a A::Value>) {
+        unsafe {
+            let new = List::<A>::link(value);
+            match self.cur {
+                Some(cur) => {
+                    self.list.link_between(new, cur.as_ref().front.get(), Some(cur));
+                    *self.index.as_mut().unwrap() += 1;
+                }
+                None => self.list.link_between(new, self.list.back, None),
+            }
+        }
+    }
+
+    // 移除当前元素，光标移到下一个（back方向）
+    // 下一个是ghost的话index变None，否则index不变
+    pub fn remove_current(&mut self) -> Option<Pin<&'a A::Value>> {
+        let cur = self.cur?;
+        unsafe {
+            self.cur = cur.as_ref().back.get();
+            if self.cur.is_none() {
+                self.index = None;
+            }
+            self.list.unlink(cur);
+            Some(List::<A>::value(cur))
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ Link, List };
+    use std::{ cell::Cell, pin::{ pin, Pin } };
+
+    #[derive(Default)]
+    struct Task {
+        id: u32,
+        runs: Cell<u32>,
+        run: Link,
+        all: Link,
+    }
+
+    impl Task {
+        fn new(id: u32) -> Self {
+            Task { id, ..Default::default() }
+        }
+    }
+
+    crate::intrusive_adapter!(RunAdapter = Task { run });
+    crate::intrusive_adapter!(AllAdapter = Task { all });
+
+    fn ids<A: super::Adapter<Value = Task>>(list: &List<'_, A>) -> Vec<u32> {
+        list.iter().map(|t| t.id).collect()
+    }
+
+    fn check_links<A: super::Adapter<Value = Task>>(list: &List<'_, A>) {
+        let forward: Vec<_> = list.iter().map(|t| t.id).collect();
+        let mut backward: Vec<_> = list.iter().rev().map(|t| t.id).collect();
+        backward.reverse();
+        assert_eq!(forward, backward);
+        assert_eq!(forward.len(), list.len());
+    }
+
+    #[test]
+    fn basics() {
+        let tasks: Vec<Pin<Box<Task>>> = (0..4).map(|i| Box::pin(Task::new(i))).collect();
+        let mut list = List::<RunAdapter>::new();
+        assert!(list.is_empty());
+        assert!(list.pop_front().is_none());
+
+        list.push_back(tasks[1].as_ref());
+        list.push_back(tasks[2].as_ref());
+        list.push_front(tasks[0].as_ref());
+        assert_eq!(list.len(), 3);
+        assert_eq!(ids(&list), [0, 1, 2]);
+        assert_eq!(list.front().unwrap().id, 0);
+        assert_eq!(list.back().unwrap().id, 2);
+        check_links(&list);
+
+        assert_eq!(list.pop_front().unwrap().id, 0);
+        assert_eq!(list.pop_back().unwrap().id, 2);
+        assert_eq!(list.len(), 1);
+
+        // 弹出来的可以再放回去
+        list.push_back(tasks[0].as_ref());
+        list.push_back(tasks[3].as_ref());
+        assert_eq!(ids(&list), [1, 0, 3]);
+        check_links(&list);
+
+        list.clear();
+        assert!(list.is_empty());
+        assert!(list.back().is_none());
+    }
+
+    #[test]
+    fn several_lists() {
+        let a = pin!(Task::new(1));
+        let b = pin!(Task::new(2));
+        let c = pin!(Task::new(3));
+        let (a, b, c) = (a.into_ref(), b.into_ref(), c.into_ref());
+
+        let mut run = List::<RunAdapter>::new();
+        let mut all = List::<AllAdapter>::new();
+        all.push_back(a);
+        all.push_back(b);
+        all.push_back(c);
+        run.push_back(c);
+        run.push_back(a);
+
+        // 通过一个链表拿到的元素，改了另一个链表里也看得到
+        for task in &run {
+            task.runs.set(task.runs.get() + 1);
+        }
+        let runs: Vec<_> = all.iter().map(|t| t.runs.get()).collect();
+        assert_eq!(runs, [1, 0, 1]);
+
+        assert_eq!(run.pop_front().unwrap().id, 3);
+        assert_eq!(ids(&run), [1]);
+        assert_eq!(ids(&all), [1, 2, 3]);
+        check_links(&run);
+        check_links(&all);
+    }
+
+    #[test]
+    fn cursor() {
+        let tasks: Vec<Pin<Box<Task>>> = (0..6).map(|i| Box::pin(Task::new(i))).collect();
+        let mut list = List::<RunAdapter>::new();
+        for task in &tasks[1..4] {
+            list.push_back(task.as_ref());
+        }
+
+        let mut cursor = list.cursor_mut();
+        assert!(cursor.current().is_none());
+        assert_eq!(cursor.peek_next().unwrap().id, 1);
+        assert_eq!(cursor.peek_prev().unwrap().id, 3);
+
+        // ghost上insert_after进front，insert_before进back
+        cursor.insert_after(tasks[0].as_ref());
+        cursor.insert_before(tasks[4].as_ref());
+        assert_eq!(cursor.index(), None);
+
+        cursor.move_next();
+        cursor.move_next();
+        assert_eq!(cursor.current().unwrap().id, 1);
+        assert_eq!(cursor.index(), Some(1));
+
+        cursor.insert_before(tasks[5].as_ref());
+        assert_eq!(cursor.index(), Some(2));
+        assert_eq!(cursor.peek_prev().unwrap().id, 5);
+
+        assert_eq!(cursor.remove_current().unwrap().id, 1);
+        assert_eq!(cursor.current().unwrap().id, 2);
+        assert_eq!(cursor.index(), Some(2));
+
+        cursor.move_prev();
+        cursor.move_prev();
+        cursor.move_prev();
+        assert!(cursor.current().is_none());
+        cursor.move_prev();
+        assert_eq!(cursor.index(), Some(4));
+        assert_eq!(cursor.remove_current().unwrap().id, 4);
+        assert!(cursor.current().is_none());
+        assert_eq!(cursor.index(), None);
+
+        assert_eq!(ids(&list), [0, 5, 2, 3]);
+        check_links(&list);
+
+        let mut cursor = list.cursor_back_mut();
+        assert_eq!(cursor.index(), Some(3));
+        cursor.insert_after(tasks[1].as_ref());
+        assert_eq!(ids(&list), [0, 5, 2, 3, 1]);
+        let mut cursor = list.cursor_front_mut();
+        while cursor.remove_current().is_some() {}
+        assert!(list.is_empty());
+    }
+
+    #[test]
+    fn drop_unlinks() {
+        let task = pin!(Task::new(0));
+        let task = task.into_ref();
+        {
+            let mut list = List::<RunAdapter>::new();
+            list.push_back(task);
+        }
+        // 链表没了，元素可以进新的链表
+        let mut list = List::<RunAdapter>::new();
+        list.push_back(task);
+        assert_eq!(ids(&list), [0]);
+    }
+
+    #[test]
+    #[should_panic(expected = "already in a list")]
+    fn double_insert() {
+        let task = pin!(Task::new(0));
+        let task = task.into_ref();
+        let mut list = List::<RunAdapter>::new();
+        list.push_back(task);
+        list.push_front(task);
+    }
+
+    #[test]
+    #[should_panic(expected = "already in a list")]
+    fn double_insert_other_list() {
+        let task = pin!(Task::new(0));
+        let task = task.into_ref();
+        let mut first = List::<RunAdapter>::new();
+        let mut second = List::<RunAdapter>::new();
+        first.push_back(task);
+        second.cursor_mut().insert_after(task);
+    }
+
+    #[test]
+    #[should_panic(expected = "already in a list")]
+    fn insert_after_forget() {
+        let task = pin!(Task::new(0));
+        let task = task.into_ref();
+        {
+            let other = pin!(Task::new(1));
+            let mut list = List::<RunAdapter>::new();
+            list.push_back(task);
+            list.push_back(other.into_ref());
+            // task的back还指着马上就没了的other
+            core::mem::forget(list);
+        }
+        let mut list = List::<RunAdapter>::new();
+        list.push_back(task);
+    }
+}
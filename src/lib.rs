@@ -5,9 +5,319 @@
 // 元素很大，否则一个节点光是链接（一两个usize的大小）都比本体大了
 // 函数式编程，链表天然适合迭代器
 
+// 链表只需要分配内存，不需要操作系统，所以默认的std feature关掉后就是no_std + alloc
+// 测试总是带着std跑，用到线程的测试另外要求std feature
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+
 pub mod first;
 pub mod second;
 pub mod third;
 pub mod fourth;
 pub mod fifth;
 pub mod sixth;
+pub mod ring;
+pub mod intrusive;
+pub mod skiplist;
+pub mod unrolled;
+pub mod xor;
+pub mod arena;
+// 要HashMap
+#[cfg(feature = "std")]
+pub mod lru;
+// 没有指针宽度CAS的目标（比如thumbv6m）上用不了
+#[cfg(target_has_atomic = "ptr")]
+pub mod concurrent;
+
+// 通用接口 =============================================================
+// 六种链表同一个概念叫法都不一样（push/append、pop/tail、pop_front……）
+// 统一成下面几个trait，泛型代码和benchmark就可以随便换实现
+// 构造统一用Default
+
+// 栈：后进先出
+pub trait Stack {
+    type Item;
+
+    fn push(&mut self, elem: Self::Item);
+    fn pop(&mut self) -> Option<Self::Item>;
+}
+
+// 队列：先进先出
+pub trait Queue {
+    type Item;
+
+    fn enqueue(&mut self, elem: Self::Item);
+    fn dequeue(&mut self) -> Option<Self::Item>;
+}
+
+// 双端队列：两头都能进出
+pub trait Deque {
+    type Item;
+
+    fn push_front(&mut self, elem: Self::Item);
+    fn push_back(&mut self, elem: Self::Item);
+    fn pop_front(&mut self) -> Option<Self::Item>;
+    fn pop_back(&mut self) -> Option<Self::Item>;
+}
+
+// 不可变栈：不改原来的，每次操作都产生一个新版本，新旧版本共享节点
+pub trait PersistentStack {
+    type Item;
+
+    fn append(&self, elem: Self::Item) -> Self;
+    fn tail(&self) -> Self;
+    fn head(&self) -> Option<&Self::Item>;
+}
+
+// 一致性测试 ===========================================================
+// 同一套行为测试，对每个实现跑一遍
+// 用法：stack_conformance!(模块名, 类型); 类型要实现Default，元素是i32
+// 测试体都写成泛型函数check，保证调用的是trait方法，而不是同名的固有方法
+// 只给本crate的测试用，不导出
+#[cfg(test)]
+macro_rules! stack_conformance {
+    ($name:ident, $ty:ty) => {
+        mod $name {
+            #[test]
+            fn lifo() {
+                fn check<L: $crate::Stack<Item = i32> + Default>() {
+                    let mut stack: L = Default::default();
+                    assert_eq!(stack.pop(), None);
+
+                    stack.push(1);
+                    stack.push(2);
+                    stack.push(3);
+                    assert_eq!(stack.pop(), Some(3));
+                    assert_eq!(stack.pop(), Some(2));
+
+                    stack.push(4);
+                    stack.push(5);
+                    assert_eq!(stack.pop(), Some(5));
+                    assert_eq!(stack.pop(), Some(4));
+                    assert_eq!(stack.pop(), Some(1));
+                    assert_eq!(stack.pop(), None);
+                    assert_eq!(stack.pop(), None);
+                }
+                check::<$ty>();
+            }
+
+            #[test]
+            fn long_drop() {
+                fn check<L: $crate::Stack<Item = i32> + Default>() {
+                    // drop不能递归，否则这里会爆栈
+                    let mut stack: L = Default::default();
+                    for i in 0..100_000 {
+                        stack.push(i);
+                    }
+                    assert_eq!(stack.pop(), Some(99_999));
+                }
+                check::<$ty>();
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+macro_rules! queue_conformance {
+    ($name:ident, $ty:ty) => {
+        mod $name {
+            #[test]
+            fn fifo() {
+                fn check<L: $crate::Queue<Item = i32> + Default>() {
+                    let mut queue: L = Default::default();
+                    assert_eq!(queue.dequeue(), None);
+
+                    queue.enqueue(1);
+                    queue.enqueue(2);
+                    queue.enqueue(3);
+                    assert_eq!(queue.dequeue(), Some(1));
+                    assert_eq!(queue.dequeue(), Some(2));
+
+                    queue.enqueue(4);
+                    queue.enqueue(5);
+                    assert_eq!(queue.dequeue(), Some(3));
+                    assert_eq!(queue.dequeue(), Some(4));
+                    assert_eq!(queue.dequeue(), Some(5));
+                    assert_eq!(queue.dequeue(), None);
+
+                    // 空了之后还能正常用
+                    queue.enqueue(6);
+                    assert_eq!(queue.dequeue(), Some(6));
+                    assert_eq!(queue.dequeue(), None);
+                }
+                check::<$ty>();
+            }
+
+            #[test]
+            fn long_drop() {
+                fn check<L: $crate::Queue<Item = i32> + Default>() {
+                    let mut queue: L = Default::default();
+                    for i in 0..100_000 {
+                        queue.enqueue(i);
+                    }
+                    assert_eq!(queue.dequeue(), Some(0));
+                }
+                check::<$ty>();
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+macro_rules! deque_conformance {
+    ($name:ident, $ty:ty) => {
+        mod $name {
+            #[test]
+            fn both_ends() {
+                fn check<L: $crate::Deque<Item = i32> + Default>() {
+                    let mut deque: L = Default::default();
+                    assert_eq!(deque.pop_front(), None);
+                    assert_eq!(deque.pop_back(), None);
+
+                    deque.push_front(2);
+                    deque.push_front(1);
+                    deque.push_back(3);
+                    deque.push_back(4);
+                    assert_eq!(deque.pop_front(), Some(1));
+                    assert_eq!(deque.pop_back(), Some(4));
+                    assert_eq!(deque.pop_back(), Some(3));
+                    assert_eq!(deque.pop_front(), Some(2));
+                    assert_eq!(deque.pop_front(), None);
+                    assert_eq!(deque.pop_back(), None);
+
+                    // 只剩一个元素时，从另一头也要能取到
+                    deque.push_front(5);
+                    assert_eq!(deque.pop_back(), Some(5));
+                    deque.push_back(6);
+                    assert_eq!(deque.pop_front(), Some(6));
+                    assert_eq!(deque.pop_front(), None);
+                    assert_eq!(deque.pop_back(), None);
+                }
+                check::<$ty>();
+            }
+
+            #[test]
+            fn long_drop() {
+                fn check<L: $crate::Deque<Item = i32> + Default>() {
+                    let mut deque: L = Default::default();
+                    for i in 0..100_000 {
+                        deque.push_back(i);
+                    }
+                    assert_eq!(deque.pop_front(), Some(0));
+                    assert_eq!(deque.pop_back(), Some(99_999));
+                }
+                check::<$ty>();
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+macro_rules! persistent_stack_conformance {
+    ($name:ident, $ty:ty) => {
+        mod $name {
+            #[test]
+            fn versions() {
+                fn check<L: $crate::PersistentStack<Item = i32> + Default>() {
+                    let empty: L = Default::default();
+                    assert_eq!(empty.head(), None);
+                    assert_eq!(empty.tail().head(), None);
+
+                    let one = empty.append(1);
+                    let two = one.append(2);
+                    let three = two.append(3);
+                    // 分叉
+                    let four = two.append(4);
+
+                    assert_eq!(three.head(), Some(&3));
+                    assert_eq!(four.head(), Some(&4));
+                    assert_eq!(three.tail().head(), Some(&2));
+                    assert_eq!(four.tail().tail().head(), Some(&1));
+                    assert_eq!(four.tail().tail().tail().head(), None);
+
+                    // 旧版本不受影响
+                    drop(three);
+                    assert_eq!(two.head(), Some(&2));
+                    assert_eq!(one.head(), Some(&1));
+                    assert_eq!(empty.head(), None);
+                }
+                check::<$ty>();
+            }
+
+            #[test]
+            fn long_drop() {
+                fn check<L: $crate::PersistentStack<Item = i32> + Default>() {
+                    let mut stack: L = Default::default();
+                    for i in 0..100_000 {
+                        stack = stack.append(i);
+                    }
+                    let shared = stack.tail();
+                    drop(stack);
+                    assert_eq!(shared.head(), Some(&99_998));
+                }
+                check::<$ty>();
+            }
+        }
+    };
+}
+
+// 测试工具 =============================================================
+// 各个链表的测试都用得上的小工具，和上面的宏一样只在测试里有
+
+// 编译期检查类型实现了哪些trait，一般是Send、Sync
+// 用法：assert_impl!(Send, Sync: 类型, 类型, ...); 写在测试函数里
+#[cfg(test)]
+macro_rules! assert_impl {
+    ($($bound:ident),+ : $($ty:ty),+ $(,)?) => {
+        {
+            fn check<T: ?Sized $(+ $bound)+>() {}
+            $(check::<$ty>();)+
+        }
+    };
+}
+
+// 协变：装&'static T的可以当成装&'a T的用，编译通过就行
+// 用法：assert_covariant!(函数名: 类型, 函数名: 类型<'i>, ...); 写在模块里
+// 类型只写路径，元素类型&T由宏补上；借用型的迭代器把它的生命周期参数写在<>里
+#[cfg(test)]
+macro_rules! assert_covariant {
+    ($($name:ident: $($seg:ident)::+ $(<$lt:lifetime>)?),+ $(,)?) => {
+        $(
+            fn $name<$($lt,)? 'a, T>(x: $($seg)::+<$($lt,)? &'static T>) -> $($seg)::+<$($lt,)? &'a T> {
+                x
+            }
+        )+
+    };
+}
+
+#[cfg(test)]
+pub(crate) use {
+    assert_covariant,
+    assert_impl,
+    deque_conformance,
+    persistent_stack_conformance,
+    queue_conformance,
+    stack_conformance,
+};
+
+#[cfg(test)]
+pub(crate) mod test_util {
+    // 按迭代顺序clone成Vec，方便和数组比较
+    pub(crate) fn to_vec<'a, T: Clone + 'a, L: ?Sized>(list: &'a L) -> Vec<T> where &'a L: IntoIterator<Item = &'a T> {
+        list.into_iter().cloned().collect()
+    }
+
+    // 随机测试用的xorshift，种子固定，失败了能复现
+    pub(crate) struct XorShift(pub(crate) u64);
+
+    impl XorShift {
+        // [0, n)里的一个数
+        pub(crate) fn below(&mut self, n: usize) -> usize {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            (self.0 % (n as u64)) as usize
+        }
+    }
+}
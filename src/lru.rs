@@ -0,0 +1,441 @@
+// LRU缓存
+// 链表最常见的正经用途：HashMap负责按key找到节点，链表负责记住使用顺序
+//     front是最近用过的，back是最久没用的，满了就从back淘汰
+// get命中后要把节点挪到front，这需要直接拿着节点，而不是下标——下标在插入删除后就失效了
+// sixth的节点分配后地址不变，map里直接存NonNull<Node>，挪动、删除都是O(1)
+//
+// key只在节点里存一份，map的key是指向节点里key的指针（KeyRef）
+// 节点不动key就不动，指针一直有效；删节点之前先从map里删掉
+//
+// HashMap要std，所以这个模块只在std feature下有
+
+use crate::sixth::{ self, LinkedList, Node };
+use core::{ borrow::Borrow, fmt::{ self, Debug }, hash::{ BuildHasher, Hash, Hasher }, ptr::NonNull };
+use std::{ collections::HashMap, hash::RandomState };
+
+pub struct LruCache<K, V, S = RandomState> {
+    map: HashMap<KeyRef<K>, NodePtr<K, V>, S>,
+    // 缓存一个节点：满了之后每次put都是先淘汰一个再插入一个，正好复用
+    list: LinkedList<(K, V)>,
+    cap: usize,
+    hits: u64,
+    misses: u64,
+}
+
+type NodePtr<K, V> = NonNull<Node<(K, V)>>;
+
+// 指向节点里的key，哈希和比较都按key本身
+struct KeyRef<K>(*const K);
+
+// 让map可以用&Q查（K: Borrow<Q>），比如key是String时用&str查
+// 不能直接给KeyRef<K>实现Borrow<Q>，会和标准库的Borrow<T> for T冲突，所以套一层
+#[repr(transparent)]
+struct KeyWrapper<Q: ?Sized>(Q);
+
+pub struct Iter<'a, K, V>(sixth::Iter<'a, (K, V)>);
+
+impl<K> Hash for KeyRef<K> where K: Hash {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        unsafe { (*self.0).hash(state) }
+    }
+}
+
+impl<K: PartialEq> PartialEq for KeyRef<K> {
+    fn eq(&self, other: &Self) -> bool {
+        unsafe { *self.0 == *other.0 }
+    }
+}
+
+impl<K: Eq> Eq for KeyRef<K> {}
+
+impl<Q: ?Sized> KeyWrapper<Q> {
+    fn from_ref(key: &Q) -> &Self {
+        // SAFETY: repr(transparent)，布局一样
+        unsafe { &*(key as *const Q as *const KeyWrapper<Q>) }
+    }
+}
+
+impl<Q: ?Sized + Hash> Hash for KeyWrapper<Q> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.hash(state)
+    }
+}
+
+impl<Q: ?Sized + PartialEq> PartialEq for KeyWrapper<Q> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl<Q: ?Sized + Eq> Eq for KeyWrapper<Q> {}
+
+impl<K: Borrow<Q>, Q: ?Sized> Borrow<KeyWrapper<Q>> for KeyRef<K> {
+    fn borrow(&self) -> &KeyWrapper<Q> {
+        KeyWrapper::from_ref(unsafe { (*self.0).borrow() })
+    }
+}
+
+impl<K: Hash + Eq, V> LruCache<K, V> {
+    // 最多存cap个，0就是什么都不存
+    pub fn new(cap: usize) -> Self {
+        Self::with_hasher(cap, RandomState::new())
+    }
+}
+
+impl<K: Hash + Eq, V, S: BuildHasher> LruCache<K, V, S> {
+    pub fn with_hasher(cap: usize, hasher: S) -> Self {
+        LruCache {
+            map: HashMap::with_hasher(hasher),
+            list: LinkedList::with_node_cache(1),
+            cap,
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.list.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.list.is_empty()
+    }
+
+    pub fn cap(&self) -> usize {
+        self.cap
+    }
+
+    // get/get_mut命中的次数
+    pub fn hits(&self) -> u64 {
+        self.hits
+    }
+
+    // get/get_mut没找到的次数
+    pub fn misses(&self) -> u64 {
+        self.misses
+    }
+
+    pub fn reset_stats(&mut self) {
+        self.hits = 0;
+        self.misses = 0;
+    }
+
+    // 查到了就变成最近用过的，计入命中/未命中
+    pub fn get<Q>(&mut self, key: &Q) -> Option<&V> where K: Borrow<Q>, Q: ?Sized + Hash + Eq {
+        self.get_mut(key).map(|value| &*value)
+    }
+
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+        where K: Borrow<Q>, Q: ?Sized + Hash + Eq
+    {
+        match self.map.get(KeyWrapper::from_ref(key)) {
+            Some(&node) => {
+                self.hits += 1;
+                unsafe {
+                    self.touch(node);
+                    Some(&mut (*node.as_ptr()).elem.1)
+                }
+            }
+            None => {
+                self.misses += 1;
+                None
+            }
+        }
+    }
+
+    // 只看不动：不改变顺序，也不计入命中/未命中
+    pub fn peek<Q>(&self, key: &Q) -> Option<&V> where K: Borrow<Q>, Q: ?Sized + Hash + Eq {
+        self.map.get(KeyWrapper::from_ref(key)).map(|node| unsafe { &(*node.as_ptr()).elem.1 })
+    }
+
+    pub fn contains<Q>(&self, key: &Q) -> bool where K: Borrow<Q>, Q: ?Sized + Hash + Eq {
+        self.map.contains_key(KeyWrapper::from_ref(key))
+    }
+
+    // 下一个要被淘汰的
+    pub fn peek_lru(&self) -> Option<(&K, &V)> {
+        self.list.back().map(|(key, value)| (key, value))
+    }
+
+    // 放进去并变成最近用过的
+    // key已经在的话换掉value，返回旧的value；满了就先淘汰最久没用的
+    pub fn put(&mut self, key: K, value: V) -> Option<V> {
+        if let Some(&node) = self.map.get(KeyWrapper::from_ref(&key)) {
+            unsafe {
+                self.touch(node);
+                return Some(core::mem::replace(&mut (*node.as_ptr()).elem.1, value));
+            }
+        }
+        if self.cap == 0 {
+            return None;
+        }
+        if self.len() == self.cap {
+            self.pop_lru();
+        }
+        let node = self.list.push_front_node((key, value));
+        let key = unsafe { &(*node.as_ptr()).elem.0 };
+        self.map.insert(KeyRef(key), node);
+        None
+    }
+
+    pub fn pop<Q>(&mut self, key: &Q) -> Option<V> where K: Borrow<Q>, Q: ?Sized + Hash + Eq {
+        let node = self.map.remove(KeyWrapper::from_ref(key))?;
+        unsafe { Some(self.list.remove_node(node).1) }
+    }
+
+    // 淘汰最久没用的
+    pub fn pop_lru(&mut self) -> Option<(K, V)> {
+        let (key, _) = self.list.back()?;
+        self.map.remove(&KeyRef(key));
+        self.list.pop_back()
+    }
+
+    // 改容量，变小了就从最久没用的开始淘汰
+    pub fn resize(&mut self, cap: usize) {
+        while self.len() > cap {
+            self.pop_lru();
+        }
+        self.cap = cap;
+    }
+
+    pub fn clear(&mut self) {
+        self.map.clear();
+        self.list.clear();
+    }
+
+    // 从最近到最久，rev()就是淘汰的顺序
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter((&self.list).into_iter())
+    }
+
+    // 挪到front
+    unsafe fn touch(&mut self, node: NodePtr<K, V>) {
+        self.list.unlink_node(node);
+        self.list.link_front_node(node);
+    }
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(|(key, value)| (key, value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl<'a, K, V> DoubleEndedIterator for Iter<'a, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.0.next_back().map(|(key, value)| (key, value))
+    }
+}
+
+impl<'a, K, V> ExactSizeIterator for Iter<'a, K, V> {
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+}
+
+impl<'a, K: Hash + Eq, V, S: BuildHasher> IntoIterator for &'a LruCache<K, V, S> {
+    type IntoIter = Iter<'a, K, V>;
+    type Item = (&'a K, &'a V);
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+// 按从久到近的顺序放进去，顺序和统计都保持一样
+impl<K: Hash + Eq + Clone, V: Clone, S: BuildHasher + Clone> Clone for LruCache<K, V, S> {
+    fn clone(&self) -> Self {
+        let mut cache = Self::with_hasher(self.cap, self.map.hasher().clone());
+        for (key, value) in self.iter().rev() {
+            cache.put(key.clone(), value.clone());
+        }
+        cache.hits = self.hits;
+        cache.misses = self.misses;
+        cache
+    }
+}
+
+impl<K: Hash + Eq + Debug, V: Debug, S: BuildHasher> Debug for LruCache<K, V, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self).finish()
+    }
+}
+
+// map里的裸指针都指向list自己的节点，整体和HashMap<K, V>一样
+unsafe impl<K: Send, V: Send, S: Send> Send for LruCache<K, V, S> {}
+unsafe impl<K: Sync, V: Sync, S: Sync> Sync for LruCache<K, V, S> {}
+
+#[cfg(test)]
+mod test {
+    use super::LruCache;
+
+    fn keys<V>(cache: &LruCache<i32, V>) -> Vec<i32> {
+        cache.iter().map(|(key, _)| *key).collect()
+    }
+
+    #[test]
+    fn basics() {
+        let mut cache = LruCache::new(2);
+        assert!(cache.is_empty());
+        assert_eq!(cache.get(&1), None);
+
+        assert_eq!(cache.put(1, "one"), None);
+        assert_eq!(cache.put(2, "two"), None);
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.get(&1), Some(&"one"));
+
+        // 2最久没用，被淘汰
+        assert_eq!(cache.put(3, "three"), None);
+        assert_eq!(cache.len(), 2);
+        assert!(!cache.contains(&2));
+        assert_eq!(keys(&cache), [3, 1]);
+
+        assert_eq!(cache.put(1, "uno"), Some("one"));
+        assert_eq!(keys(&cache), [1, 3]);
+        *cache.get_mut(&3).unwrap() = "tres";
+        assert_eq!(cache.peek_lru(), Some((&1, &"uno")));
+        assert_eq!(cache.iter().collect::<Vec<_>>(), [(&3, &"tres"), (&1, &"uno")]);
+    }
+
+    #[test]
+    fn peek_keeps_order() {
+        let mut cache = LruCache::new(3);
+        cache.put(1, 10);
+        cache.put(2, 20);
+        cache.put(3, 30);
+        assert_eq!(cache.peek(&1), Some(&10));
+        assert_eq!(cache.peek(&4), None);
+        assert_eq!(keys(&cache), [3, 2, 1]);
+        assert_eq!((cache.hits(), cache.misses()), (0, 0));
+
+        cache.put(4, 40);
+        assert_eq!(keys(&cache), [4, 3, 2]);
+        assert_eq!(cache.iter().rev().map(|(key, _)| *key).collect::<Vec<_>>(), [2, 3, 4]);
+    }
+
+    #[test]
+    fn pop() {
+        let mut cache = LruCache::new(4);
+        for i in 0..4 {
+            cache.put(i, i * 10);
+        }
+        assert_eq!(cache.pop(&2), Some(20));
+        assert_eq!(cache.pop(&2), None);
+        assert_eq!(cache.pop_lru(), Some((0, 0)));
+        assert_eq!(keys(&cache), [3, 1]);
+        cache.get(&1);
+        assert_eq!(cache.pop_lru(), Some((3, 30)));
+        assert_eq!(cache.pop_lru(), Some((1, 10)));
+        assert_eq!(cache.pop_lru(), None);
+        assert_eq!(cache.peek_lru(), None);
+        assert!(cache.map.is_empty());
+    }
+
+    #[test]
+    fn resize() {
+        let mut cache = LruCache::new(5);
+        for i in 0..5 {
+            cache.put(i, ());
+        }
+        cache.resize(2);
+        assert_eq!(cache.cap(), 2);
+        assert_eq!(keys(&cache), [4, 3]);
+        cache.resize(3);
+        cache.put(5, ());
+        assert_eq!(keys(&cache), [5, 4, 3]);
+
+        // 容量0什么都不存
+        cache.resize(0);
+        assert!(cache.is_empty());
+        assert_eq!(cache.put(6, ()), None);
+        assert!(cache.is_empty());
+        assert_eq!(cache.get(&6), None);
+    }
+
+    #[test]
+    fn stats() {
+        let mut cache = LruCache::new(2);
+        cache.put("a", 1);
+        cache.get(&"a");
+        cache.get(&"a");
+        cache.get(&"b");
+        cache.get_mut(&"b");
+        cache.peek(&"a");
+        assert_eq!((cache.hits(), cache.misses()), (2, 2));
+        cache.reset_stats();
+        assert_eq!((cache.hits(), cache.misses()), (0, 0));
+    }
+
+    #[test]
+    fn borrowed_keys() {
+        let mut cache: LruCache<String, usize> = LruCache::new(2);
+        cache.put("hello".to_string(), 1);
+        cache.put(String::from("world"), 2);
+        assert_eq!(cache.get("hello"), Some(&1));
+        assert_eq!(cache.peek("world"), Some(&2));
+        assert!(cache.contains("world"));
+        assert_eq!(cache.pop("world"), Some(2));
+        assert_eq!(format!("{:?}", cache), r#"{"hello": 1}"#);
+    }
+
+    #[test]
+    fn drops() {
+        use std::rc::Rc;
+
+        let counter = Rc::new(());
+        let mut cache = LruCache::new(3);
+        for i in 0..10 {
+            cache.put(i, counter.clone());
+        }
+        assert_eq!(Rc::strong_count(&counter), 4);
+        cache.put(9, counter.clone());
+        assert_eq!(Rc::strong_count(&counter), 4);
+        drop(cache.pop(&8));
+        assert_eq!(Rc::strong_count(&counter), 3);
+        cache.clear();
+        assert_eq!(Rc::strong_count(&counter), 1);
+
+        cache.put(0, counter.clone());
+        drop(cache);
+        assert_eq!(Rc::strong_count(&counter), 1);
+    }
+
+    #[test]
+    fn clone() {
+        let mut cache = LruCache::new(3);
+        cache.put(1, 'a');
+        cache.put(2, 'b');
+        cache.put(3, 'c');
+        cache.get(&1);
+        let mut cloned = cache.clone();
+        assert_eq!(cloned.iter().collect::<Vec<_>>(), cache.iter().collect::<Vec<_>>());
+        assert_eq!(cloned.hits(), 1);
+        cloned.put(4, 'd');
+        assert_eq!(keys(&cloned), [4, 1, 3]);
+        assert_eq!(keys(&cache), [1, 3, 2]);
+    }
+
+    // 满了之后一直put，淘汰下来的节点直接复用
+    #[test]
+    fn reuses_evicted_node() {
+        let mut cache = LruCache::new(1);
+        cache.put(0, 0);
+        let node = cache.map.values().next().copied();
+        for i in 1..100 {
+            cache.put(i, i);
+            assert_eq!(cache.map.values().next().copied(), node);
+        }
+        assert_eq!(keys(&cache), [99]);
+    }
+
+    #[test]
+    fn send_sync() {
+        crate::assert_impl!(Send, Sync: LruCache<i32, String>);
+    }
+}
@@ -0,0 +1,816 @@
+// 环形双链表
+// sixth的back和front之间有个ghost，光标走到头会停在那；轮转调度要的是走过最后一个自动回到第一个
+// 这里把back.back接回front、front.front接回back，首尾相连，没有None也就没有ghost
+//
+// 环没有固定的头，只记着“当前”节点cur，转一圈的顺序从cur开始：
+//     cur是front，cur.front是back
+// rotate_next/rotate_prev只是把cur挪一格，O(1)
+//
+// 节点布局和sixth完全一样（同一个Node，都用Global分配），
+// 所以和sixth::LinkedList互相转换只要把首尾接上或者断开，O(1)
+
+use crate::sixth::{ Global, LinkedList, Link, Node, NodeAllocator };
+use ::alloc::alloc::Layout;
+use core::{ fmt::{ self, Debug }, marker::PhantomData, mem, ptr::{ self, NonNull } };
+
+// 迭代器和sixth的一样只靠len停下，直接拿来用
+pub use crate::sixth::{ IntoIter, Iter, IterMut };
+
+pub struct Ring<T> {
+    cur: Link<T>,
+    len: usize,
+    _boo: PhantomData<T>,
+}
+
+// 永远指着某个元素，只有环是空的时候cur才是None
+// index是从环的cur（转一圈的起点）数起的位置
+pub struct CursorMut<'a, T> {
+    ring: &'a mut Ring<T>,
+    cur: Link<T>,
+    index: Option<usize>,
+}
+
+impl<T> Ring<T> {
+    pub fn new() -> Self {
+        Ring {
+            cur: None,
+            len: 0,
+            _boo: PhantomData,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    // 当前元素，也是转一圈的第一个
+    pub fn front(&self) -> Option<&T> {
+        unsafe { self.cur.map(|node| &(*node.as_ptr()).elem) }
+    }
+
+    pub fn front_mut(&mut self) -> Option<&mut T> {
+        unsafe { self.cur.map(|node| &mut (*node.as_ptr()).elem) }
+    }
+
+    // 转一圈的最后一个，也就是cur的前一个
+    pub fn back(&self) -> Option<&T> {
+        unsafe { self.back_node().map(|node| &(*node.as_ptr()).elem) }
+    }
+
+    pub fn back_mut(&mut self) -> Option<&mut T> {
+        unsafe { self.back_node().map(|node| &mut (*node.as_ptr()).elem) }
+    }
+
+    // 往后转一格：原来的第二个变成当前，原来的当前变成最后一个
+    pub fn rotate_next(&mut self) {
+        if let Some(cur) = self.cur {
+            self.cur = unsafe { (*cur.as_ptr()).back };
+        }
+    }
+
+    pub fn rotate_prev(&mut self) {
+        self.cur = self.back_node();
+    }
+
+    // 插到最后（cur前面），下一圈最后才轮到
+    pub fn push_back(&mut self, elem: T) {
+        unsafe {
+            let new = new_node(elem);
+            self.link_chain_before(self.cur, new, new, 1);
+        }
+    }
+
+    // 插到最前并成为当前元素
+    pub fn push_front(&mut self, elem: T) {
+        self.push_back(elem);
+        self.rotate_prev();
+    }
+
+    // 移除当前元素，下一个成为当前
+    pub fn pop_front(&mut self) -> Option<T> {
+        self.cur.map(|node| unsafe { self.unlink(node) })
+    }
+
+    pub fn pop_back(&mut self) -> Option<T> {
+        self.back_node().map(|node| unsafe { self.unlink(node) })
+    }
+
+    pub fn clear(&mut self) {
+        drop(self.take_list());
+    }
+
+    // 从cur开始转一圈
+    pub fn iter(&self) -> Iter<'_, T> {
+        unsafe { Iter::from_raw(self.cur, self.back_node(), self.len) }
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        unsafe { IterMut::from_raw(self.cur, self.back_node(), self.len) }
+    }
+
+    pub fn cursor_mut(&mut self) -> CursorMut<'_, T> {
+        CursorMut {
+            cur: self.cur,
+            index: self.cur.map(|_| 0),
+            ring: self,
+        }
+    }
+
+    // 在cur和back之间断开，cur成为链表的front
+    pub fn into_list(mut self) -> LinkedList<T> {
+        self.take_list()
+    }
+
+    // 链表的back接回front，front成为cur
+    pub fn from_list(list: LinkedList<T>) -> Self {
+        let mut ring = Self::new();
+        ring.join(list);
+        ring
+    }
+
+    // 把链表整个接到环的最后（cur前面），O(1)
+    pub fn join(&mut self, list: LinkedList<T>) {
+        let (front, back, len) = list.into_raw_parts();
+        if let (Some(front), Some(back)) = (front, back) {
+            unsafe { self.link_chain_before(self.cur, front, back, len) }
+        }
+    }
+
+    // 环里留下从cur数起的at个，剩下的按原来的顺序拿出来变成链表
+    // 要走到第at个，从近的那头走，O(min(at, len - at))
+    pub fn split_off(&mut self, at: usize) -> LinkedList<T> {
+        assert!(at <= self.len, "Cannot split off at a nonexistent index");
+        if at == 0 {
+            return self.take_list();
+        }
+        if at == self.len {
+            return LinkedList::new();
+        }
+        unsafe {
+            let front = self.cur.unwrap();
+            let back = (*front.as_ptr()).front.unwrap();
+            let mut first = front;
+            if at <= self.len / 2 {
+                for _ in 0..at {
+                    first = (*first.as_ptr()).back.unwrap();
+                }
+            } else {
+                for _ in at..self.len {
+                    first = (*first.as_ptr()).front.unwrap();
+                }
+            }
+            // first..back拿出去，first的前一个接回cur
+            let last = (*first.as_ptr()).front.unwrap();
+            (*last.as_ptr()).back = Some(front);
+            (*front.as_ptr()).front = Some(last);
+            (*first.as_ptr()).front = None;
+            (*back.as_ptr()).back = None;
+
+            let len = self.len - at;
+            self.len = at;
+            LinkedList::from_raw_parts(Some(first), Some(back), len)
+        }
+    }
+
+    // 节点操作 ======================================
+    fn back_node(&self) -> Link<T> {
+        self.cur.and_then(|node| unsafe { (*node.as_ptr()).front })
+    }
+
+    // 把front..back这串（首尾不连）插到at前面；at为None说明环是空的，这串首尾相连成为整个环
+    // SAFETY: 节点都是Global分配的，不在任何环或链表里
+    unsafe fn link_chain_before(
+        &mut self,
+        at: Link<T>,
+        front: NonNull<Node<T>>,
+        back: NonNull<Node<T>>,
+        len: usize
+    ) {
+        let (prev, next) = match at {
+            Some(at) => ((*at.as_ptr()).front.unwrap(), at),
+            None => {
+                self.cur = Some(front);
+                (back, front)
+            }
+        };
+        (*prev.as_ptr()).back = Some(front);
+        (*front.as_ptr()).front = Some(prev);
+        (*back.as_ptr()).back = Some(next);
+        (*next.as_ptr()).front = Some(back);
+        self.len += len;
+    }
+
+    // 摘下一个节点并取出元素，摘的是cur的话cur挪到下一个
+    unsafe fn unlink(&mut self, node: NonNull<Node<T>>) -> T {
+        if self.len == 1 {
+            self.cur = None;
+        } else {
+            let prev = (*node.as_ptr()).front.unwrap();
+            let next = (*node.as_ptr()).back.unwrap();
+            (*prev.as_ptr()).back = Some(next);
+            (*next.as_ptr()).front = Some(prev);
+            if self.cur == Some(node) {
+                self.cur = Some(next);
+            }
+        }
+        self.len -= 1;
+        free_node(node)
+    }
+
+    fn take_list(&mut self) -> LinkedList<T> {
+        let len = mem::replace(&mut self.len, 0);
+        match self.cur.take() {
+            Some(front) => unsafe {
+                let back = (*front.as_ptr()).front.take().unwrap();
+                (*back.as_ptr()).back = None;
+                LinkedList::from_raw_parts(Some(front), Some(back), len)
+            }
+            None => LinkedList::new(),
+        }
+    }
+}
+
+// 和LinkedList<T>（Global）一样的分配方式
+fn new_node<T>(elem: T) -> NonNull<Node<T>> {
+    let node = Global.allocate(Layout::new::<Node<T>>()).cast::<Node<T>>();
+    unsafe {
+        node.as_ptr().write(Node {
+            front: None,
+            back: None,
+            elem,
+        });
+    }
+    node
+}
+
+// SAFETY: node是new_node分配的（或者来自LinkedList<T>），已经摘下来了
+unsafe fn free_node<T>(node: NonNull<Node<T>>) -> T {
+    let elem = ptr::read(&(*node.as_ptr()).elem);
+    Global.deallocate(node.cast(), Layout::new::<Node<T>>());
+    elem
+}
+
+// 断开成链表，交给链表去释放
+impl<T> Drop for Ring<T> {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+// 光标 ===========================================
+// 走到最后一个再move_next就回到第一个，永远不会停在ghost上
+impl<'a, T> CursorMut<'a, T> {
+    pub fn index(&self) -> Option<usize> {
+        self.index
+    }
+
+    pub fn current(&mut self) -> Option<&mut T> {
+        unsafe { self.cur.map(|node| &mut (*node.as_ptr()).elem) }
+    }
+
+    pub fn peek_next(&mut self) -> Option<&mut T> {
+        unsafe { self.cur.map(|node| &mut (*(*node.as_ptr()).back.unwrap().as_ptr()).elem) }
+    }
+
+    pub fn peek_prev(&mut self) -> Option<&mut T> {
+        unsafe { self.cur.map(|node| &mut (*(*node.as_ptr()).front.unwrap().as_ptr()).elem) }
+    }
+
+    pub fn move_next(&mut self) {
+        if let (Some(cur), Some(index)) = (self.cur, self.index) {
+            self.cur = unsafe { (*cur.as_ptr()).back };
+            self.index = Some((index + 1) % self.ring.len);
+        }
+    }
+
+    pub fn move_prev(&mut self) {
+        if let (Some(cur), Some(index)) = (self.cur, self.index) {
+            self.cur = unsafe { (*cur.as_ptr()).front };
+            self.index = Some(index.checked_sub(1).unwrap_or(self.ring.len - 1));
+        }
+    }
+
+    // 把环的起点转到光标这，index变0
+    pub fn rotate_to_current(&mut self) {
+        if self.cur.is_some() {
+            self.ring.cur = self.cur;
+            self.index = Some(0);
+        }
+    }
+
+    // 插到当前元素后面；环是空的话光标指向新元素
+    pub fn insert_after(&mut self, elem: T) {
+        match self.cur {
+            Some(cur) => unsafe {
+                let new = new_node(elem);
+                self.ring.link_chain_before((*cur.as_ptr()).back, new, new, 1);
+            }
+            None => {
+                self.ring.push_back(elem);
+                self.reset();
+            }
+        }
+    }
+
+    // 插到当前元素前面
+    // 当前是起点的话，新元素排在一圈的最后，index还是0；否则index加1
+    pub fn insert_before(&mut self, elem: T) {
+        match self.cur {
+            Some(cur) => {
+                unsafe {
+                    let new = new_node(elem);
+                    self.ring.link_chain_before(Some(cur), new, new, 1);
+                }
+                self.bump_index(1);
+            }
+            None => {
+                self.ring.push_back(elem);
+                self.reset();
+            }
+        }
+    }
+
+    // 移除当前元素，光标移到下一个（走到头就回到起点）
+    // 绕着环一边走一边删就靠这个
+    pub fn remove_current(&mut self) -> Option<T> {
+        let (cur, index) = (self.cur?, self.index?);
+        unsafe {
+            let next = (*cur.as_ptr()).back;
+            let elem = self.ring.unlink(cur);
+            if self.ring.is_empty() {
+                self.cur = None;
+                self.index = None;
+            } else {
+                self.cur = next;
+                self.index = Some(if index == self.ring.len { 0 } else { index });
+            }
+            Some(elem)
+        }
+    }
+
+    // 把链表插到当前元素后面，index不变
+    pub fn splice_after(&mut self, list: LinkedList<T>) {
+        match self.cur {
+            Some(cur) => {
+                let (front, back, len) = list.into_raw_parts();
+                if let (Some(front), Some(back)) = (front, back) {
+                    unsafe { self.ring.link_chain_before((*cur.as_ptr()).back, front, back, len) }
+                }
+            }
+            None => {
+                self.ring.join(list);
+                self.reset();
+            }
+        }
+    }
+
+    // 把链表插到当前元素前面
+    // 当前是起点的话插进来的排在一圈的最后，否则index加上插入的个数
+    pub fn splice_before(&mut self, list: LinkedList<T>) {
+        match self.cur {
+            Some(cur) => {
+                let (front, back, len) = list.into_raw_parts();
+                if let (Some(front), Some(back)) = (front, back) {
+                    unsafe { self.ring.link_chain_before(Some(cur), front, back, len) }
+                    self.bump_index(len);
+                }
+            }
+            None => {
+                self.ring.join(list);
+                self.reset();
+            }
+        }
+    }
+
+    fn reset(&mut self) {
+        self.cur = self.ring.cur;
+        self.index = self.cur.map(|_| 0);
+    }
+
+    fn bump_index(&mut self, n: usize) {
+        if let Some(index) = self.index.as_mut() {
+            if *index != 0 {
+                *index += n;
+            }
+        }
+    }
+}
+
+// 迭代器 =========================================
+// 断开成链表再用链表的IntoIter，顺序是从cur转一圈
+impl<T> IntoIterator for Ring<T> {
+    type IntoIter = IntoIter<T>;
+    type Item = T;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.into_list().into_iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a Ring<T> {
+    type IntoIter = Iter<'a, T>;
+    type Item = &'a T;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut Ring<T> {
+    type IntoIter = IterMut<'a, T>;
+    type Item = &'a mut T;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+// ================================================
+impl<T> From<LinkedList<T>> for Ring<T> {
+    fn from(list: LinkedList<T>) -> Self {
+        Self::from_list(list)
+    }
+}
+
+impl<T> From<Ring<T>> for LinkedList<T> {
+    fn from(ring: Ring<T>) -> Self {
+        ring.into_list()
+    }
+}
+
+impl<T> Default for Ring<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Clone> Clone for Ring<T> {
+    fn clone(&self) -> Self {
+        self.iter().cloned().collect()
+    }
+}
+
+impl<T> Extend<T> for Ring<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for item in iter {
+            self.push_back(item);
+        }
+    }
+}
+
+impl<T> FromIterator<T> for Ring<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut ring = Self::new();
+        ring.extend(iter);
+        ring
+    }
+}
+
+impl<T: Debug> Debug for Ring<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self).finish()
+    }
+}
+
+// 从各自的cur开始比，转过的环和原来的不相等
+impl<T: PartialEq> PartialEq for Ring<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.into_iter().eq(other)
+    }
+}
+
+impl<T: Eq> Eq for Ring<T> {}
+
+unsafe impl<T: Send> Send for Ring<T> {}
+unsafe impl<T: Sync> Sync for Ring<T> {}
+
+// 通用接口 =======================================
+// 栈从front进出，队列从back进front出
+impl<T> crate::Stack for Ring<T> {
+    type Item = T;
+
+    fn push(&mut self, elem: T) {
+        self.push_front(elem)
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        self.pop_front()
+    }
+}
+
+impl<T> crate::Queue for Ring<T> {
+    type Item = T;
+
+    fn enqueue(&mut self, elem: T) {
+        self.push_back(elem)
+    }
+
+    fn dequeue(&mut self) -> Option<T> {
+        self.pop_front()
+    }
+}
+
+impl<T> crate::Deque for Ring<T> {
+    type Item = T;
+
+    fn push_front(&mut self, elem: T) {
+        self.push_front(elem)
+    }
+
+    fn push_back(&mut self, elem: T) {
+        self.push_back(elem)
+    }
+
+    fn pop_front(&mut self) -> Option<T> {
+        self.pop_front()
+    }
+
+    fn pop_back(&mut self) -> Option<T> {
+        self.pop_back()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Ring;
+    use crate::sixth::LinkedList;
+    use crate::test_util::to_vec;
+
+    crate::stack_conformance!(stack, super::Ring<i32>);
+    crate::queue_conformance!(queue, super::Ring<i32>);
+    crate::deque_conformance!(deque, super::Ring<i32>);
+
+    // 沿back转一圈回到起点，沿front也一样
+    fn check<T>(ring: &Ring<T>) {
+        let Some(start) = ring.cur else {
+            assert_eq!(ring.len(), 0);
+            return;
+        };
+        unsafe {
+            let mut node = start;
+            for _ in 0..ring.len() {
+                let next = (*node.as_ptr()).back.unwrap();
+                assert_eq!((*next.as_ptr()).front, Some(node));
+                node = next;
+            }
+            assert_eq!(node, start);
+        }
+    }
+
+    #[test]
+    fn basics() {
+        let mut ring = Ring::new();
+        assert_eq!(ring.pop_front(), None);
+        assert_eq!(ring.pop_back(), None);
+        ring.rotate_next();
+        ring.rotate_prev();
+
+        ring.push_back(1);
+        check(&ring);
+        assert_eq!(ring.front(), Some(&1));
+        assert_eq!(ring.back(), Some(&1));
+        ring.push_back(2);
+        ring.push_back(3);
+        ring.push_front(0);
+        check(&ring);
+        assert_eq!(to_vec(&ring), [0, 1, 2, 3]);
+        assert_eq!(ring.front(), Some(&0));
+        assert_eq!(ring.back(), Some(&3));
+
+        assert_eq!(ring.pop_front(), Some(0));
+        assert_eq!(ring.pop_back(), Some(3));
+        *ring.front_mut().unwrap() += 10;
+        *ring.back_mut().unwrap() += 20;
+        assert_eq!(to_vec(&ring), [11, 22]);
+        ring.clear();
+        assert!(ring.is_empty());
+        assert_eq!(ring.front(), None);
+    }
+
+    #[test]
+    fn rotate() {
+        let mut ring: Ring<i32> = (0..4).collect();
+        ring.rotate_next();
+        assert_eq!(to_vec(&ring), [1, 2, 3, 0]);
+        ring.rotate_next();
+        ring.rotate_next();
+        ring.rotate_next();
+        assert_eq!(to_vec(&ring), [0, 1, 2, 3]);
+        ring.rotate_prev();
+        assert_eq!(to_vec(&ring), [3, 0, 1, 2]);
+        assert_eq!(ring.iter().rev().copied().collect::<Vec<_>>(), [2, 1, 0, 3]);
+
+        // 转完再两头进出
+        ring.push_back(4);
+        ring.push_front(5);
+        check(&ring);
+        assert_eq!(to_vec(&ring), [5, 3, 0, 1, 2, 4]);
+    }
+
+    #[test]
+    fn iter() {
+        let mut ring: Ring<i32> = (0..5).collect();
+        ring.rotate_next();
+        let mut iter = ring.iter();
+        assert_eq!(iter.len(), 5);
+        assert_eq!(iter.next(), Some(&1));
+        assert_eq!(iter.next_back(), Some(&0));
+        assert_eq!(iter.collect::<Vec<_>>(), [&2, &3, &4]);
+
+        for elem in &mut ring {
+            *elem *= 10;
+        }
+        assert_eq!(to_vec(&ring), [10, 20, 30, 40, 0]);
+        assert_eq!(ring.into_iter().rev().collect::<Vec<_>>(), [0, 40, 30, 20, 10]);
+        assert!(Ring::<i32>::new().iter().next().is_none());
+    }
+
+    #[test]
+    fn cursor_wraps() {
+        let mut ring: Ring<i32> = (0..3).collect();
+        let mut cursor = ring.cursor_mut();
+        assert_eq!(cursor.index(), Some(0));
+        assert_eq!(cursor.peek_prev(), Some(&mut 2));
+        cursor.move_prev();
+        assert_eq!(cursor.current(), Some(&mut 2));
+        assert_eq!(cursor.index(), Some(2));
+        cursor.move_next();
+        assert_eq!(cursor.current(), Some(&mut 0));
+        assert_eq!(cursor.index(), Some(0));
+        // 走很多圈也不会停
+        for _ in 0..100 {
+            cursor.move_next();
+        }
+        assert_eq!(cursor.index(), Some(1));
+        assert_eq!(cursor.current(), Some(&mut 1));
+        assert_eq!(cursor.peek_next(), Some(&mut 2));
+
+        cursor.rotate_to_current();
+        assert_eq!(cursor.index(), Some(0));
+        assert_eq!(to_vec(&ring), [1, 2, 0]);
+
+        let mut empty = Ring::<i32>::new();
+        let mut cursor = empty.cursor_mut();
+        cursor.move_next();
+        cursor.move_prev();
+        assert_eq!(cursor.current(), None);
+        assert_eq!(cursor.index(), None);
+        assert_eq!(cursor.remove_current(), None);
+        cursor.insert_after(7);
+        assert_eq!(cursor.current(), Some(&mut 7));
+        assert_eq!(cursor.index(), Some(0));
+    }
+
+    #[test]
+    fn cursor_insert_remove() {
+        let mut ring: Ring<i32> = (0..4).collect();
+        let mut cursor = ring.cursor_mut();
+        cursor.move_next();
+        cursor.insert_after(10);
+        cursor.insert_before(20);
+        assert_eq!(cursor.current(), Some(&mut 1));
+        assert_eq!(cursor.index(), Some(2));
+
+        // 在起点前面插，排到最后
+        cursor.move_prev();
+        cursor.move_prev();
+        assert_eq!(cursor.index(), Some(0));
+        cursor.insert_before(30);
+        assert_eq!(cursor.index(), Some(0));
+        check(&ring);
+        assert_eq!(to_vec(&ring), [0, 20, 1, 10, 2, 3, 30]);
+
+        // 删最后一个，光标回到起点
+        let mut cursor = ring.cursor_mut();
+        cursor.move_prev();
+        assert_eq!(cursor.remove_current(), Some(30));
+        assert_eq!(cursor.index(), Some(0));
+        assert_eq!(cursor.current(), Some(&mut 0));
+        // 删起点，环的起点跟着挪
+        assert_eq!(cursor.remove_current(), Some(0));
+        assert_eq!(cursor.index(), Some(0));
+        assert_eq!(ring.front(), Some(&20));
+        check(&ring);
+        assert_eq!(to_vec(&ring), [20, 1, 10, 2, 3]);
+    }
+
+    // 轮转调度：每个任务每轮做一份工作，做完的从环上摘掉
+    #[test]
+    fn round_robin() {
+        let mut ring: Ring<(char, u32)> = [('a', 3), ('b', 1), ('c', 2)].into_iter().collect();
+        let mut order = Vec::new();
+        let mut cursor = ring.cursor_mut();
+        while let Some((name, left)) = cursor.current() {
+            order.push(*name);
+            *left -= 1;
+            if *left == 0 {
+                cursor.remove_current();
+            } else {
+                cursor.move_next();
+            }
+        }
+        assert_eq!(order, ['a', 'b', 'c', 'a', 'c', 'a']);
+        assert!(ring.is_empty());
+    }
+
+    #[test]
+    fn lists() {
+        let list: LinkedList<i32> = (0..5).collect();
+        let mut ring = Ring::from(list);
+        check(&ring);
+        ring.rotate_next();
+        ring.rotate_next();
+        let list = LinkedList::from(ring);
+        assert_eq!((&list).into_iter().copied().collect::<Vec<_>>(), [2, 3, 4, 0, 1]);
+        assert_eq!(list.len(), 5);
+
+        let mut ring = Ring::from_list(list);
+        ring.join((5..7).collect());
+        ring.join(LinkedList::new());
+        check(&ring);
+        assert_eq!(to_vec(&ring), [2, 3, 4, 0, 1, 5, 6]);
+
+        let tail = ring.split_off(2);
+        assert_eq!((&tail).into_iter().copied().collect::<Vec<_>>(), [4, 0, 1, 5, 6]);
+        check(&ring);
+        assert_eq!(to_vec(&ring), [2, 3]);
+        let mut ring: Ring<i32> = (0..6).collect();
+        let tail = ring.split_off(5);
+        assert_eq!((&tail).into_iter().copied().collect::<Vec<_>>(), [5]);
+        assert!(ring.split_off(5).is_empty());
+        let all = ring.split_off(0);
+        assert_eq!(all.len(), 5);
+        assert!(ring.is_empty());
+        assert_eq!(ring.cur, None);
+
+        // 链表拿出来还能正常用，也能按链表的方式释放
+        let mut all = all;
+        all.push_back(9);
+        assert_eq!(all.pop_front(), Some(0));
+        assert_eq!(Ring::from(all).into_list().into_iter().collect::<Vec<_>>(), [1, 2, 3, 4, 9]);
+        assert!(Ring::from(LinkedList::<i32>::new()).is_empty());
+    }
+
+    #[test]
+    fn cursor_splice() {
+        let mut ring: Ring<i32> = (0..3).collect();
+        let mut cursor = ring.cursor_mut();
+        cursor.move_next();
+        cursor.splice_after((10..12).collect());
+        cursor.splice_before((20..22).collect());
+        assert_eq!(cursor.index(), Some(3));
+        assert_eq!(cursor.current(), Some(&mut 1));
+        cursor.splice_before(LinkedList::new());
+        assert_eq!(cursor.index(), Some(3));
+        check(&ring);
+        assert_eq!(to_vec(&ring), [0, 20, 21, 1, 10, 11, 2]);
+
+        let mut empty = Ring::new();
+        let mut cursor = empty.cursor_mut();
+        cursor.splice_before((0..2).collect());
+        assert_eq!(cursor.current(), Some(&mut 0));
+        cursor.splice_after((5..6).collect());
+        assert_eq!(to_vec(&empty), [0, 5, 1]);
+    }
+
+    #[test]
+    fn traits() {
+        let ring: Ring<i32> = (0..3).collect();
+        let mut other = ring.clone();
+        assert_eq!(ring, other);
+        assert_eq!(format!("{:?}", other), "[0, 1, 2]");
+        other.rotate_next();
+        assert_ne!(ring, other);
+        assert_eq!(format!("{:?}", other), "[1, 2, 0]");
+        assert!(Ring::<i32>::default().is_empty());
+    }
+
+    #[test]
+    fn drops() {
+        use std::rc::Rc;
+
+        let counter = Rc::new(());
+        let mut ring: Ring<_> = (0..10).map(|_| counter.clone()).collect();
+        ring.rotate_next();
+        drop(ring.pop_back());
+        let mut cursor = ring.cursor_mut();
+        drop(cursor.remove_current());
+        let list = ring.split_off(3);
+        assert_eq!(Rc::strong_count(&counter), 9);
+        drop(list);
+        assert_eq!(Rc::strong_count(&counter), 4);
+        drop(ring);
+        assert_eq!(Rc::strong_count(&counter), 1);
+    }
+
+    #[test]
+    fn send_sync() {
+        crate::assert_impl!(Send, Sync: Ring<i32>);
+    }
+
+    crate::assert_covariant!(_covariant: Ring);
+}
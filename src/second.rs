@@ -1,6 +1,8 @@
 // 2 An Ok Stack
 // 基本的单链栈，无头部哨兵
 
+use alloc::boxed::Box;
+
 pub struct List<T> {
     head: Link<T>,
 }
@@ -53,6 +55,25 @@ impl<T> List<T> {
     pub fn peek_mut(&mut self) -> Option<&mut T> {
         self.head.as_mut().map(|node| &mut node.elem)
     }
+
+    // 按栈顶到栈底的顺序建栈，第一个就是栈顶
+    // 拿着最后一个节点的next往后接，不用先收集再倒着push
+    #[cfg_attr(not(target_has_atomic = "ptr"), allow(dead_code))]
+    pub(crate) fn from_top_down<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut list = List::new();
+        let mut tail = &mut list.head;
+        for elem in iter {
+            let node = tail.insert(Box::new(Node { elem, next: None }));
+            tail = &mut node.next;
+        }
+        list
+    }
+}
+
+impl<T> Default for List<T> {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 // 手动drop用循环取代递归
@@ -91,7 +112,7 @@ impl<'a, T> IntoIterator for &'a List<T> {
 
     fn into_iter(self) -> Self::IntoIter {
         Iter {
-            next: self.head.as_ref().map(|node| &**node), //node:&Box<Node<T>>, *->box, *->&, &add&
+            next: self.head.as_deref(), //node:&Box<Node<T>>, *->box, *->&, &add&
         }
     }
 }
@@ -103,7 +124,7 @@ impl<'a, T> IntoIterator for &'a mut List<T> {
 
     fn into_iter(self) -> Self::IntoIter {
         IterMut {
-            next: self.head.as_mut().map(|node| &mut **node),
+            next: self.head.as_deref_mut(),
         }
     }
 }
@@ -123,7 +144,7 @@ impl<'a, T> Iterator for Iter<'a, T> {
     fn next(&mut self) -> Option<Self::Item> {
         // 不为None才map，否则也是None
         self.next.map(|node| {
-            self.next = node.next.as_ref().map(|node| &**node);
+            self.next = node.next.as_deref();
             &node.elem
         })
     }
@@ -134,16 +155,30 @@ impl<'a, T> Iterator for IterMut<'a, T> {
 
     fn next(&mut self) -> Option<Self::Item> {
         self.next.take().map(|node| {
-            self.next = node.next.as_mut().map(|node| &mut **node);
+            self.next = node.next.as_deref_mut();
             &mut node.elem
         })
     }
 }
 //=========================================================
+impl<T> crate::Stack for List<T> {
+    type Item = T;
+
+    fn push(&mut self, elem: T) {
+        self.push(elem)
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        self.pop()
+    }
+}
 
 #[cfg(test)]
 mod test {
     use super::List;
+
+    crate::stack_conformance!(stack, super::List<i32>);
+
     #[test]
     fn basics() {
         let mut list = List::new();
@@ -184,9 +219,9 @@ mod test {
 
         assert_eq!(list.peek(), Some(&3));
         assert_eq!(list.peek_mut(), Some(&mut 3));
-        list.peek_mut().map(|value| {
+        if let Some(value) = list.peek_mut() {
             *value = 42;
-        });
+        }
 
         assert_eq!(list.peek(), Some(&42));
         assert_eq!(list.pop(), Some(42));
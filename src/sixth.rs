@@ -38,14 +38,19 @@
 
 // type Link<T> = *mut Node<T>;
 
-use std::{
+use ::alloc::alloc::{ self, Layout };
+use core::{
     cmp::Ordering,
     fmt::{ self, Debug },
     marker::PhantomData,
-    ptr::NonNull,
+    ptr::{ self, NonNull },
     hash::{ Hash, Hasher },
 };
 
+mod handle;
+pub use handle::{ HandleError, NodeHandle };
+use handle::Handles;
+
 // struct Node<T> {
 //     front: Link<T>,
 //     back: Link<T>,
@@ -54,13 +59,82 @@ use std::{
 // =====================================================================
 // 这个是协变的
 // 因为NonNull是一个*const指针
-pub struct LinkedList<T> {
+pub struct LinkedList<T, A: NodeAllocator = Global> {
     front: Link<T>,
     back: Link<T>,
     len: usize,
+    alloc: A,
+    // 节点缓存：pop下来的空节点用back串成单链，push时优先复用
+    // 缓存里节点的elem是未初始化的，不能drop也不能读
+    cache: Link<T>,
+    cache_len: usize,
+    cache_max: usize, // 0就是不缓存
+    // 发出去的NodeHandle，没发过就是空的
+    handles: Handles<T>,
     _boo: PhantomData<T>, // PhantomData是一个体积为0的假类型
 }
 
+// 节点分配器 =====================================
+// 标准库的Allocator还在nightly，这里自己定义一个够链表用的
+// 分配的都是Node<T>，所以只要按Layout分配、释放就行
+
+/// 链表节点的分配器
+///
+/// # Safety
+///
+/// allocate返回的内存必须满足layout，而且在deallocate之前一直有效；
+/// same_as返回true的两个分配器，必须可以互相释放对方分配的内存
+pub unsafe trait NodeAllocator {
+    // 分配失败自己处理（比如handle_alloc_error），不返回空指针
+    fn allocate(&self, layout: Layout) -> NonNull<u8>;
+
+    /// # Safety
+    ///
+    /// ptr必须是这个分配器（或same_as的分配器）用同样的layout分配的，而且还没释放过
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout);
+
+    // 是不是同一个分配器实例
+    // split、splice、append只能在同一个实例的链表之间进行，否则节点会被错误的分配器释放
+    fn same_as(&self, other: &Self) -> bool;
+}
+
+// 默认的全局分配器，也就是Box用的那个
+#[derive(Clone, Copy, Default, Debug)]
+pub struct Global;
+
+unsafe impl NodeAllocator for Global {
+    fn allocate(&self, layout: Layout) -> NonNull<u8> {
+        // Node至少有两个指针，layout不会是0大小
+        match NonNull::new(unsafe { alloc::alloc(layout) }) {
+            Some(ptr) => ptr,
+            None => alloc::handle_alloc_error(layout),
+        }
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        alloc::dealloc(ptr.as_ptr(), layout)
+    }
+
+    fn same_as(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+// 借用一个分配器，比如多个链表共用一块arena
+unsafe impl<A: NodeAllocator + ?Sized> NodeAllocator for &A {
+    fn allocate(&self, layout: Layout) -> NonNull<u8> {
+        (**self).allocate(layout)
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        (**self).deallocate(ptr, layout)
+    }
+
+    fn same_as(&self, other: &Self) -> bool {
+        ptr::eq(*self, *other) || (**self).same_as(*other)
+    }
+}
+
 // PhantomData有多个用处：
 // 1、某个生命周期只在方法中用到，成员并没有，编译器会提示结构体成员没有这个生命周期
 // 2、类型只在方法中用到
@@ -69,16 +143,17 @@ pub struct LinkedList<T> {
 // 用NonNull是因为NonNull包含的指针是*const T，是协变的
 // 数据结构的协变可以保证派生出的不可变引用也是协变的
 // 否则就都是invariant了
-type Link<T> = Option<NonNull<Node<T>>>; // NonNull必须保证不为空，但因为有可能为空，就用Option
+pub(crate) type Link<T> = Option<NonNull<Node<T>>>; // NonNull必须保证不为空，但因为有可能为空，就用Option
 
-struct Node<T> {
-    front: Link<T>,
-    back: Link<T>,
-    elem: T,
+// ring和链表共用这个节点布局，两边互相转换时节点原样交接
+pub(crate) struct Node<T> {
+    pub(crate) front: Link<T>,
+    pub(crate) back: Link<T>,
+    pub(crate) elem: T,
 }
 
-pub struct IntoIter<T> {
-    list: LinkedList<T>,
+pub struct IntoIter<T, A: NodeAllocator = Global> {
+    list: LinkedList<T, A>,
 }
 pub struct Iter<'a, T> {
     front: Link<T>,
@@ -94,34 +169,149 @@ pub struct IterMut<'a, T> {
 }
 
 // 光标
-pub struct CursorMut<'a, T> {
-    list: &'a mut LinkedList<T>,
+pub struct CursorMut<'a, T, A: NodeAllocator = Global> {
+    list: &'a mut LinkedList<T, A>,
+    cur: Link<T>,
+    index: Option<usize>,
+}
+
+// 边走边摘，摘下满足条件的节点并吐出元素
+// 每摘一个len就减一，中途drop或者pred panic都不会让len对不上
+pub struct ExtractIf<'a, T, F: FnMut(&mut T) -> bool, A: NodeAllocator = Global> {
+    list: &'a mut LinkedList<T, A>,
+    it: Link<T>,
+    remaining: usize,
+    pred: F,
+}
+
+// 只读光标，和CursorMut一样有个ghost位置（在back和front之间）
+// 只借了&LinkedList，所以可以和Iter、其它Cursor共存
+pub struct Cursor<'a, T, A: NodeAllocator = Global> {
+    list: &'a LinkedList<T, A>,
     cur: Link<T>,
     index: Option<usize>,
 }
 
 impl<T> LinkedList<T> {
     pub fn new() -> Self {
+        Self::new_in(Global)
+    }
+
+    // 最多缓存max个pop下来的节点，给后面的push复用
+    // 适合push_back、pop_front反复交替的队列用法，省掉一次分配一次释放
+    pub fn with_node_cache(max: usize) -> Self {
+        Self::with_node_cache_in(max, Global)
+    }
+
+    // 把front..back这串节点交出去，缓存留下跟着self释放
+    // 只给Global的链表用，接手的一方（ring）也用Global分配、释放节点
+    pub(crate) fn into_raw_parts(mut self) -> (Link<T>, Link<T>, usize) {
+        let parts = (self.front.take(), self.back.take(), self.len);
+        self.len = 0;
+        parts
+    }
+
+    // SAFETY: front..back是一串用Global分配的、首尾不连的节点，一共len个
+    pub(crate) unsafe fn from_raw_parts(front: Link<T>, back: Link<T>, len: usize) -> Self {
+        let mut list = Self::new();
+        list.front = front;
+        list.back = back;
+        list.len = len;
+        list
+    }
+}
+
+impl<T, A: NodeAllocator> LinkedList<T, A> {
+    pub fn new_in(alloc: A) -> Self {
+        Self::with_node_cache_in(0, alloc)
+    }
+
+    pub fn with_node_cache_in(max: usize, alloc: A) -> Self {
         Self {
             front: None,
             back: None,
             len: 0,
+            alloc,
+            cache: None,
+            cache_len: 0,
+            cache_max: max,
+            handles: Handles::new(),
             _boo: PhantomData,
         }
     }
 
+    pub fn allocator(&self) -> &A {
+        &self.alloc
+    }
+
+    // 当前缓存着的空节点个数
+    pub fn node_cache_len(&self) -> usize {
+        self.cache_len
+    }
+
+    // 把缓存的节点全还给分配器
+    pub fn shrink_node_cache(&mut self) {
+        while let Some(node) = self.cache {
+            unsafe {
+                self.cache = (*node.as_ptr()).back;
+                self.alloc.deallocate(node.cast(), Layout::new::<Node<T>>());
+            }
+        }
+        self.cache_len = 0;
+    }
+
+    // 原来是Box::into_raw(Box::new(node))，现在先看缓存，没有再交给分配器
+    fn new_node(&mut self, front: Link<T>, back: Link<T>, elem: T) -> NonNull<Node<T>> {
+        let node = match self.cache {
+            Some(node) => {
+                self.cache = unsafe { (*node.as_ptr()).back };
+                self.cache_len -= 1;
+                node
+            }
+            None => self.alloc.allocate(Layout::new::<Node<T>>()).cast::<Node<T>>(),
+        };
+        unsafe {
+            // 缓存的节点elem是未初始化的，用write整个覆盖，不会drop旧值
+            node.as_ptr().write(Node { front, back, elem });
+        }
+        node
+    }
+
+    // 原来是Box::from_raw(node)，把元素移出来，节点放进缓存或者释放
+    // SAFETY: node是这个链表的分配器分配的，并且已经从链表上摘下来了
+    unsafe fn free_node(&mut self, node: NonNull<Node<T>>) -> T {
+        // 元素没了，指着它的句柄作废
+        self.handles.release(node);
+        let elem = ptr::read(&(*node.as_ptr()).elem);
+        if self.cache_len < self.cache_max {
+            (*node.as_ptr()).front = None;
+            (*node.as_ptr()).back = self.cache;
+            self.cache = Some(node);
+            self.cache_len += 1;
+        } else {
+            self.alloc.deallocate(node.cast(), Layout::new::<Node<T>>());
+        }
+        elem
+    }
+
+    // 节点全部转给一个新链表，自己变空
+    // 缓存和缓存设置留在自己这，不能用mem::replace整个换掉
+    fn take_nodes(&mut self) -> Self
+    where
+        A: Clone,
+    {
+        let mut output = Self::new_in(self.alloc.clone());
+        self.handles.reset();
+        output.front = self.front.take();
+        output.back = self.back.take();
+        output.len = core::mem::replace(&mut self.len, 0);
+        output
+    }
+
     pub fn push_front(&mut self, elem: T) {
         // SAFETY: it's a linked-list, what do you want?
         unsafe {
-            let new = NonNull::new_unchecked(
-                Box::into_raw(
-                    Box::new(Node {
-                        front: None,
-                        back: None,
-                        elem,
-                    })
-                )
-            );
+            let new = self.new_node(None, None, elem);
             if let Some(old) = self.front {
                 // Put the new front before the old one
                 // Nonnull不能被解引用
@@ -144,15 +334,7 @@ impl<T> LinkedList<T> {
     pub fn push_back(&mut self, elem: T) {
         // SAFETY: it's a linked-list, what do you want?
         unsafe {
-            let new = NonNull::new_unchecked(
-                Box::into_raw(
-                    Box::new(Node {
-                        back: None,
-                        front: None,
-                        elem,
-                    })
-                )
-            );
+            let new = self.new_node(None, None, elem);
             if let Some(old) = self.back {
                 // Put the new back before the old one
                 (*old.as_ptr()).back = Some(new);
@@ -172,14 +354,13 @@ impl<T> LinkedList<T> {
         unsafe {
             // 函数式编程，不take了
             self.front.map(|node| {
-                // Bring the Box back to life so we can move out its value and
-                // Drop it (Box continues to magically understand this for us).
-                // 多亏了零成本抽象
-                let boxed_node = Box::from_raw(node.as_ptr());
-                let result = boxed_node.elem;
+                // Move the value out and hand the node back to the allocator.
+                // 以前是Box::from_raw，Box帮我们释放；现在分配器可以换，得自己释放
+                let next = (*node.as_ptr()).back;
+                let result = self.free_node(node);
 
                 // Make the next node into the new front.
-                self.front = boxed_node.back;
+                self.front = next;
                 if let Some(new) = self.front {
                     // Cleanup its reference to the removed node
                     (*new.as_ptr()).front = None;
@@ -198,8 +379,7 @@ impl<T> LinkedList<T> {
                 // 以上两种情况都是panic之后仍在运行程序
                 self.len -= 1;
                 result
-                // Box gets implicitly freed here, knows there is no T
-                // 节点的前后指针都不拥有前后者的所有权，不会被连带释放掉？
+                // 节点的前后指针都不拥有前后者的所有权，不会被连带释放掉
             })
         }
     }
@@ -208,13 +388,12 @@ impl<T> LinkedList<T> {
         unsafe {
             // Only have to do stuff if there is a back node to pop.
             self.back.map(|node| {
-                // Bring the Box front to life so we can move out its value and
-                // Drop it (Box continues to magically understand this for us).
-                let boxed_node = Box::from_raw(node.as_ptr());
-                let result = boxed_node.elem;
+                // Move the value out and hand the node back to the allocator.
+                let prev = (*node.as_ptr()).front;
+                let result = self.free_node(node);
 
                 // Make the next node into the new back.
-                self.back = boxed_node.front;
+                self.back = prev;
                 if let Some(new) = self.back {
                     // Cleanup its reference to the removed node
                     (*new.as_ptr()).back = None;
@@ -225,7 +404,6 @@ impl<T> LinkedList<T> {
 
                 self.len -= 1;
                 result
-                // Box gets implicitly freed here, knows there is no T.
             })
         }
     }
@@ -256,29 +434,411 @@ impl<T> LinkedList<T> {
 
     pub fn clear(&mut self) {
         // Pop until we have to stop
-        while let Some(_) = self.pop_front() {}
+        while self.pop_front().is_some() {}
+        // pop的时候节点可能进了缓存，一起还掉
+        self.shrink_node_cache();
+    }
+
+    // 把other整个接到后面，other变空
+    // 只改两头的指针，O(1)
+    pub fn append(&mut self, other: &mut Self) {
+        assert!(self.alloc.same_as(&other.alloc), "Cannot append lists with different allocators");
+        // 句柄是跟着链表的，other的句柄全部作废
+        other.handles.reset();
+        // 不能直接swap，两边的节点缓存要各留各的
+        match self.back {
+            None => {
+                self.front = other.front.take();
+                self.back = other.back.take();
+            }
+            Some(back) => {
+                if let Some(other_front) = other.front.take() {
+                    unsafe {
+                        (*back.as_ptr()).back = Some(other_front);
+                        (*other_front.as_ptr()).front = Some(back);
+                    }
+                    self.back = other.back.take();
+                }
+            }
+        }
+        self.len += core::mem::replace(&mut other.len, 0);
+    }
+
+    // 把other整个接到前面，other变空
+    pub fn prepend(&mut self, other: &mut Self) {
+        assert!(self.alloc.same_as(&other.alloc), "Cannot prepend lists with different allocators");
+        other.handles.reset();
+        match self.front {
+            None => {
+                self.front = other.front.take();
+                self.back = other.back.take();
+            }
+            Some(front) => {
+                if let Some(other_back) = other.back.take() {
+                    unsafe {
+                        (*front.as_ptr()).front = Some(other_back);
+                        (*other_back.as_ptr()).back = Some(front);
+                    }
+                    self.front = other.front.take();
+                }
+            }
+        }
+        self.len += core::mem::replace(&mut other.len, 0);
+    }
+
+    // 从at处分成两半，返回[at, len)，自己留下[0, at)
+    // at > len 会panic
+    pub fn split_off(&mut self, at: usize) -> LinkedList<T, A>
+    where
+        A: Clone,
+    {
+        assert!(at <= self.len, "Cannot split off at a nonexistent index");
+        if at == 0 {
+            return self.take_nodes();
+        }
+        // 借光标的split_after，省得再写一遍断链
+        let cur = self.node_at(at - 1);
+        CursorMut {
+            list: self,
+            cur,
+            index: Some(at - 1),
+        }.split_after()
+    }
+
+    // 保留pred返回true的，其余的按顺序drop掉
+    pub fn retain<F: FnMut(&T) -> bool>(&mut self, mut pred: F) {
+        self.retain_mut(|elem| pred(elem))
+    }
+
+    pub fn retain_mut<F: FnMut(&mut T) -> bool>(&mut self, mut pred: F) {
+        for _ in self.extract_if(|elem| !pred(elem)) {}
+    }
+
+    // 懒的，迭代到哪摘到哪
+    pub fn extract_if<F: FnMut(&mut T) -> bool>(&mut self, pred: F) -> ExtractIf<'_, T, F, A> {
+        ExtractIf {
+            it: self.front,
+            remaining: self.len,
+            list: self,
+            pred,
+        }
+    }
+
+    // 把node从链表上摘下来，node的front、back清空，len减一
+    // SAFETY: node必须是这个链表里的节点
+    pub(crate) unsafe fn unlink_node(&mut self, node: NonNull<Node<T>>) {
+        let prev = (*node.as_ptr()).front;
+        let next = (*node.as_ptr()).back;
+
+        match prev {
+            Some(prev) => (*prev.as_ptr()).back = next,
+            None => self.front = next,
+        }
+        match next {
+            Some(next) => (*next.as_ptr()).front = prev,
+            None => self.back = prev,
+        }
+        (*node.as_ptr()).front = None;
+        (*node.as_ptr()).back = None;
+
+        self.len -= 1;
+    }
+
+    // 把摘下来的节点接到最前面，len加一
+    // SAFETY: node是这个链表的分配器分配的，元素有效，不在任何链表上
+    pub(crate) unsafe fn link_front_node(&mut self, node: NonNull<Node<T>>) {
+        (*node.as_ptr()).front = None;
+        (*node.as_ptr()).back = self.front;
+        match self.front {
+            Some(old) => (*old.as_ptr()).front = Some(node),
+            None => self.back = Some(node),
+        }
+        self.front = Some(node);
+        self.len += 1;
+    }
+
+    // 同push_front，另外返回新节点
+    // 节点在被pop或者remove_node之前地址不变，lru靠这个在外面记着节点（lru要std）
+    #[cfg_attr(not(feature = "std"), allow(dead_code))]
+    pub(crate) fn push_front_node(&mut self, elem: T) -> NonNull<Node<T>> {
+        self.push_front(elem);
+        self.front.unwrap()
+    }
+
+    // SAFETY: node必须是这个链表里的节点
+    pub(crate) unsafe fn remove_node(&mut self, node: NonNull<Node<T>>) -> T {
+        self.unlink_node(node);
+        self.free_node(node)
     }
 
-    pub fn cursor_mut(&mut self) -> CursorMut<T> {
+    // 从离得近的一头开始找，最多走len/2步
+    fn node_at(&self, index: usize) -> Link<T> {
+        if index >= self.len {
+            return None;
+        }
+        unsafe {
+            if index <= self.len / 2 {
+                let mut cur = self.front;
+                for _ in 0..index {
+                    cur = (*cur?.as_ptr()).back;
+                }
+                cur
+            } else {
+                let mut cur = self.back;
+                for _ in 0..self.len - 1 - index {
+                    cur = (*cur?.as_ptr()).front;
+                }
+                cur
+            }
+        }
+    }
+
+    pub fn sort(&mut self)
+    where
+        T: Ord,
+    {
+        self.sort_by(T::cmp)
+    }
+
+    pub fn sort_by_key<K: Ord, F: FnMut(&T) -> K>(&mut self, mut f: F) {
+        self.sort_by(|a, b| f(a).cmp(&f(b)))
+    }
+
+    // 自底向上的归并排序，稳定
+    // 只改节点的front、back指针，T不动也不分配内存
+    // 排序期间只把back当单链表用，最后由SortGuard统一修front
+    pub fn sort_by<F: FnMut(&T, &T) -> Ordering>(&mut self, mut compare: F) {
+        if self.len < 2 {
+            return;
+        }
+
+        let mut guard = SortGuard {
+            head: None,
+            tail: None,
+            p: None,
+            psize: 0,
+            rest: self.front,
+            list: self,
+        };
+
+        // 每轮把相邻两段长为run的有序段合并，直到只剩一段
+        let mut run = 1;
+        loop {
+            let mut merges = 0;
+            while let Some(p) = guard.rest {
+                merges += 1;
+
+                // p段：从rest里数出最多run个
+                guard.p = Some(p);
+                guard.psize = 0;
+                let mut q = Some(p);
+                while guard.psize < run {
+                    guard.psize += 1;
+                    q = unsafe { (*q.unwrap().as_ptr()).back };
+                    if q.is_none() {
+                        break;
+                    }
+                }
+                // q段就是rest的开头
+                guard.rest = q;
+                let mut qsize = run;
+
+                unsafe {
+                    while guard.psize > 0 || (qsize > 0 && guard.rest.is_some()) {
+                        // 相等时取p，保证稳定
+                        let take_p = if guard.psize == 0 {
+                            false
+                        } else if qsize == 0 || guard.rest.is_none() {
+                            true
+                        } else {
+                            let p = guard.p.unwrap();
+                            let q = guard.rest.unwrap();
+                            compare(&(*p.as_ptr()).elem, &(*q.as_ptr()).elem) != Ordering::Greater
+                        };
+
+                        let node = if take_p {
+                            let node = guard.p.unwrap();
+                            guard.p = (*node.as_ptr()).back;
+                            guard.psize -= 1;
+                            node
+                        } else {
+                            let node = guard.rest.unwrap();
+                            guard.rest = (*node.as_ptr()).back;
+                            qsize -= 1;
+                            node
+                        };
+
+                        match guard.tail {
+                            Some(tail) => (*tail.as_ptr()).back = Some(node),
+                            None => guard.head = Some(node),
+                        }
+                        guard.tail = Some(node);
+                    }
+                }
+            }
+
+            if let Some(tail) = guard.tail {
+                unsafe { (*tail.as_ptr()).back = None };
+            }
+            if merges <= 1 {
+                break;
+            }
+
+            // 下一轮
+            run *= 2;
+            guard.rest = guard.head.take();
+            guard.tail = None;
+        }
+        // guard在这里drop，修好front指针
+    }
+
+    pub fn is_sorted_by<F: FnMut(&T, &T) -> bool>(&self, mut compare: F) -> bool {
+        let mut iter = self.into_iter();
+        let mut last = match iter.next() {
+            Some(elem) => elem,
+            None => {
+                return true;
+            }
+        };
+        for elem in iter {
+            if !compare(last, elem) {
+                return false;
+            }
+            last = elem;
+        }
+        true
+    }
+
+    pub fn cursor_mut(&mut self) -> CursorMut<'_, T, A> {
         CursorMut {
             list: self,
             cur: None,
             index: None,
         }
     }
+
+    // 指向front，空链表则指向ghost
+    pub fn cursor_front_mut(&mut self) -> CursorMut<'_, T, A> {
+        CursorMut {
+            cur: self.front,
+            index: self.front.map(|_| 0),
+            list: self,
+        }
+    }
+
+    // 指向back，空链表则指向ghost
+    pub fn cursor_back_mut(&mut self) -> CursorMut<'_, T, A> {
+        CursorMut {
+            cur: self.back,
+            index: self.back.map(|_| self.len - 1),
+            list: self,
+        }
+    }
+
+    // 指向index处的元素，从离得近的一头走过去
+    // index == len 指向ghost（相当于最后一个元素后面），再大就panic
+    pub fn cursor_at_mut(&mut self, index: usize) -> CursorMut<'_, T, A> {
+        assert!(index <= self.len, "Cannot move cursor to a nonexistent index");
+        CursorMut {
+            cur: self.node_at(index),
+            index: Some(index).filter(|&index| index < self.len),
+            list: self,
+        }
+    }
+
+    // 指向front，空链表则指向ghost
+    pub fn cursor_front(&self) -> Cursor<'_, T, A> {
+        Cursor {
+            list: self,
+            cur: self.front,
+            index: self.front.map(|_| 0),
+        }
+    }
+
+    // 指向back，空链表则指向ghost
+    pub fn cursor_back(&self) -> Cursor<'_, T, A> {
+        Cursor {
+            list: self,
+            cur: self.back,
+            index: self.back.map(|_| self.len - 1),
+        }
+    }
 }
 
-impl<T> Drop for LinkedList<T> {
+impl<T, A: NodeAllocator> Drop for LinkedList<T, A> {
     fn drop(&mut self) {
         self.clear()
     }
 }
 
+// 排序中途的状态，节点分布在三段里：
+// head..tail（已合并） ++ p开始的psize个（p段剩下的） ++ rest开始到结尾（q段剩下的和后面没处理的）
+// 各段尾部的back可能是旧的，drop时按顺序重新接起来，再从头修一遍front
+// 比较函数panic的时候也会走这里，所以链表总是完整的
+struct SortGuard<'a, T, A: NodeAllocator> {
+    list: &'a mut LinkedList<T, A>,
+    head: Link<T>,
+    tail: Link<T>,
+    p: Link<T>,
+    psize: usize,
+    rest: Link<T>,
+}
+
+impl<'a, T, A: NodeAllocator> Drop for SortGuard<'a, T, A> {
+    fn drop(&mut self) {
+        unsafe {
+            let mut head = self.head;
+            let mut tail = self.tail;
+
+            if self.psize > 0 {
+                let p = self.p.unwrap();
+                match tail {
+                    Some(tail) => (*tail.as_ptr()).back = Some(p),
+                    None => head = Some(p),
+                }
+                let mut last = p;
+                for _ in 1..self.psize {
+                    last = (*last.as_ptr()).back.unwrap();
+                }
+                tail = Some(last);
+            }
+
+            if let Some(rest) = self.rest {
+                match tail {
+                    Some(tail) => (*tail.as_ptr()).back = Some(rest),
+                    None => head = Some(rest),
+                }
+                // rest本身就是好的单链，走到尾就行
+                let mut last = rest;
+                while let Some(next) = (*last.as_ptr()).back {
+                    last = next;
+                }
+                tail = Some(last);
+            }
+
+            if let Some(tail) = tail {
+                (*tail.as_ptr()).back = None;
+            }
+
+            let mut prev = None;
+            let mut cur = head;
+            while let Some(node) = cur {
+                (*node.as_ptr()).front = prev;
+                prev = cur;
+                cur = (*node.as_ptr()).back;
+            }
+
+            self.list.front = head;
+            self.list.back = prev;
+        }
+    }
+}
+
 // 迭代器：实现IntoIterator、Iterator、DoubleEndedIterator（支持倒着迭代next_back）、ExactSizeIterator（支持获取长度len）
 // 只要引入接口就能访问接口、这几个trait都很散
 // IntoIter =======================================
-impl<T> IntoIterator for LinkedList<T> {
-    type IntoIter = IntoIter<T>;
+impl<T, A: NodeAllocator> IntoIterator for LinkedList<T, A> {
+    type IntoIter = IntoIter<T, A>;
     type Item = T;
 
     fn into_iter(self) -> Self::IntoIter {
@@ -288,7 +848,7 @@ impl<T> IntoIterator for LinkedList<T> {
     }
 }
 
-impl<T> Iterator for IntoIter<T> {
+impl<T, A: NodeAllocator> Iterator for IntoIter<T, A> {
     type Item = T;
 
     fn next(&mut self) -> Option<Self::Item> {
@@ -300,19 +860,19 @@ impl<T> Iterator for IntoIter<T> {
     }
 }
 
-impl<T> DoubleEndedIterator for IntoIter<T> {
+impl<T, A: NodeAllocator> DoubleEndedIterator for IntoIter<T, A> {
     fn next_back(&mut self) -> Option<Self::Item> {
         self.list.pop_back()
     }
 }
 
-impl<T> ExactSizeIterator for IntoIter<T> {
+impl<T, A: NodeAllocator> ExactSizeIterator for IntoIter<T, A> {
     fn len(&self) -> usize {
         self.list.len
     }
 }
 // Iter ===========================================
-impl<'a, T> IntoIterator for &'a LinkedList<T> {
+impl<'a, T, A: NodeAllocator> IntoIterator for &'a LinkedList<T, A> {
     type IntoIter = Iter<'a, T>;
     type Item = &'a T;
 
@@ -326,6 +886,19 @@ impl<'a, T> IntoIterator for &'a LinkedList<T> {
     }
 }
 
+impl<'a, T> Iter<'a, T> {
+    // 迭代器只靠len停下，不看首尾是不是None，所以首尾相连的ring也能直接用
+    // SAFETY: 从front沿back走len步、从back沿front走len步都是活着的节点，'a期间没人改
+    pub(crate) unsafe fn from_raw(front: Link<T>, back: Link<T>, len: usize) -> Self {
+        Iter {
+            front,
+            back,
+            len,
+            _boo: PhantomData,
+        }
+    }
+}
+
 impl<'a, T> Iterator for Iter<'a, T> {
     type Item = &'a T;
 
@@ -375,7 +948,7 @@ impl<'a, T> ExactSizeIterator for Iter<'a, T> {
     }
 }
 // IterMut ========================================
-impl<'a, T> IntoIterator for &'a mut LinkedList<T> {
+impl<'a, T, A: NodeAllocator> IntoIterator for &'a mut LinkedList<T, A> {
     type IntoIter = IterMut<'a, T>;
     type Item = &'a mut T;
 
@@ -389,6 +962,18 @@ impl<'a, T> IntoIterator for &'a mut LinkedList<T> {
     }
 }
 
+impl<'a, T> IterMut<'a, T> {
+    // SAFETY: 同Iter::from_raw，另外'a期间这些节点只能通过这个迭代器访问
+    pub(crate) unsafe fn from_raw(front: Link<T>, back: Link<T>, len: usize) -> Self {
+        IterMut {
+            front,
+            back,
+            len,
+            _boo: PhantomData,
+        }
+    }
+}
+
 impl<'a, T> Iterator for IterMut<'a, T> {
     type Item = &'a mut T;
 
@@ -432,13 +1017,46 @@ impl<'a, T> ExactSizeIterator for IterMut<'a, T> {
         self.len
     }
 }
+// ExtractIf ======================================
+impl<'a, T, F: FnMut(&mut T) -> bool, A: NodeAllocator> Iterator for ExtractIf<'a, T, F, A> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(node) = self.it {
+            unsafe {
+                // 先往后走，再判断，摘掉node也不影响it
+                self.it = (*node.as_ptr()).back;
+                self.remaining -= 1;
+                if (self.pred)(&mut (*node.as_ptr()).elem) {
+                    self.list.unlink_node(node);
+                    return Some(self.list.free_node(node));
+                }
+            }
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.remaining))
+    }
+}
+
 // 光标 ===========================================
 // 光标装饰器就是用来找中间的值的，还有分裂
-impl<'a, T> CursorMut<'a, T> {
+impl<'a, T, A: NodeAllocator> CursorMut<'a, T, A> {
     pub fn index(&self) -> Option<usize> {
         self.index
     }
 
+    // 再借一个只读光标，借期内CursorMut不能动
+    pub fn as_cursor(&self) -> Cursor<'_, T, A> {
+        Cursor {
+            list: self.list,
+            cur: self.cur,
+            index: self.index,
+        }
+    }
+
     pub fn move_next(&mut self) {
         if let Some(cur) = self.cur {
             unsafe {
@@ -481,6 +1099,50 @@ impl<'a, T> CursorMut<'a, T> {
         }
     }
 
+    // 跳到index处，index == len 是ghost，再大就panic
+    // 从当前位置、front、back三个起点里挑最近的走
+    pub fn seek(&mut self, index: usize) {
+        let len = self.list.len;
+        assert!(index <= len, "Cannot move cursor to a nonexistent index");
+        if index == len {
+            self.cur = None;
+            self.index = None;
+            return;
+        }
+
+        // 从front走要index步，从back走要len - 1 - index步
+        let from_ends = index.min(len - 1 - index);
+        match (self.cur, self.index) {
+            (Some(mut cur), Some(cur_index)) if cur_index.abs_diff(index) < from_ends => {
+                unsafe {
+                    if index > cur_index {
+                        for _ in cur_index..index {
+                            cur = (*cur.as_ptr()).back.unwrap();
+                        }
+                    } else {
+                        for _ in index..cur_index {
+                            cur = (*cur.as_ptr()).front.unwrap();
+                        }
+                    }
+                }
+                self.cur = Some(cur);
+            }
+            _ => {
+                self.cur = self.list.node_at(index);
+            }
+        }
+        self.index = Some(index);
+    }
+
+    // 相当于n为正时move_next n次，为负时move_prev -n次
+    // ghost也算一个位置，所以是在len + 1个位置的环上走
+    pub fn move_by(&mut self, n: isize) {
+        let ring = self.list.len + 1;
+        let pos = self.index.unwrap_or(self.list.len);
+        let steps = n.rem_euclid(ring as isize) as usize;
+        self.seek((pos + steps) % ring);
+    }
+
     pub fn current(&mut self) -> Option<&mut T> {
         unsafe { self.cur.map(|node| &mut (*node.as_ptr()).elem) }
     }
@@ -515,7 +1177,117 @@ impl<'a, T> CursorMut<'a, T> {
         }
     }
 
-    pub fn split_before(&mut self) -> LinkedList<T> {
+    // 在当前元素后插入，ghost上则插到front
+    // index不变
+    pub fn insert_after(&mut self, elem: T) {
+        if let Some(cur) = self.cur {
+            unsafe {
+                if let Some(next) = (*cur.as_ptr()).back {
+                    let new = self.list.new_node(Some(cur), Some(next), elem);
+                    (*cur.as_ptr()).back = Some(new);
+                    (*next.as_ptr()).front = Some(new);
+                    self.list.len += 1;
+                } else {
+                    // cur就是back
+                    self.list.push_back(elem);
+                }
+            }
+        } else {
+            self.list.push_front(elem);
+        }
+    }
+
+    // 在当前元素前插入，ghost上则插到back
+    // 当前元素往后挪了一位，所以index加1
+    pub fn insert_before(&mut self, elem: T) {
+        if let Some(cur) = self.cur {
+            unsafe {
+                if let Some(prev) = (*cur.as_ptr()).front {
+                    let new = self.list.new_node(Some(prev), Some(cur), elem);
+                    (*prev.as_ptr()).back = Some(new);
+                    (*cur.as_ptr()).front = Some(new);
+                    self.list.len += 1;
+                } else {
+                    // cur就是front
+                    self.list.push_front(elem);
+                }
+            }
+            *self.index.as_mut().unwrap() += 1;
+        } else {
+            self.list.push_back(elem);
+        }
+    }
+
+    // 移除当前元素，光标移到下一个（back方向）
+    // 下一个是ghost的话index变None，否则index不变
+    pub fn remove_current(&mut self) -> Option<T> {
+        let cur = self.unlink_current()?;
+        unsafe { Some(self.list.free_node(cur)) }
+    }
+
+    // 同remove_current，但节点不释放，作为只有一个元素的链表返回
+    pub fn remove_current_as_list(&mut self) -> Option<LinkedList<T, A>>
+    where
+        A: Clone,
+    {
+        // We have this:
+        //
+        //     list.front -> A <-> B <-> C <- list.back
+        //                         ^
+        //                        cur
+        //
+        // And we want to produce this:
+        //
+        //     list.front -> A <-> C <- list.back
+        //                         ^
+        //                        cur
+        //
+        //    return.front -> B <- return.back
+        //
+        let cur = self.unlink_current()?;
+        self.list.handles.release(cur);
+        Some(LinkedList {
+            front: Some(cur),
+            back: Some(cur),
+            len: 1,
+            alloc: self.list.alloc.clone(),
+            cache: None,
+            cache_len: 0,
+            cache_max: 0,
+            handles: Handles::new(),
+            _boo: PhantomData,
+        })
+    }
+
+    // 摘下当前节点，光标移到下一个，节点交给调用者处理
+    fn unlink_current(&mut self) -> Link<T> {
+        let cur = self.cur?;
+        unsafe {
+            let next = (*cur.as_ptr()).back;
+            self.list.unlink_node(cur);
+
+            self.cur = next;
+            if next.is_none() {
+                // We just walked to the ghost, no more index
+                self.index = None;
+            }
+        }
+        Some(cur)
+    }
+
+    // 替换当前元素，返回旧值
+    // ghost上没有元素可换，原样把elem还回去
+    pub fn replace_current(&mut self, elem: T) -> Result<T, T> {
+        match self.current() {
+            Some(cur) => Ok(core::mem::replace(cur, elem)),
+            None => Err(elem),
+        }
+    }
+
+    pub fn split_before(&mut self) -> LinkedList<T, A>
+    where
+        A: Clone,
+    {
         // We have this:
         //
         //     list.front -> A <-> B <-> C <-> D <- list.back
@@ -547,8 +1319,9 @@ impl<'a, T> CursorMut<'a, T> {
                 let new_idx = Some(0);
 
                 // What the output will become
+                // cur就是front时输出为空，front也得是None
                 let output_len = old_len - new_len;
-                let output_front = self.list.front;
+                let output_front = prev.and(self.list.front);
                 let output_back = prev;
 
                 // Break the links between cur and prev
@@ -562,22 +1335,32 @@ impl<'a, T> CursorMut<'a, T> {
                 self.list.front = new_front;
                 self.list.back = new_back;
                 self.index = new_idx;
+                // 分出去的节点上的句柄作废
+                self.list.handles.release_chain(output_front, output_len);
 
                 LinkedList {
                     front: output_front,
                     back: output_back,
                     len: output_len,
+                    alloc: self.list.alloc.clone(),
+                    cache: None,
+                    cache_len: 0,
+                    cache_max: 0,
+                    handles: Handles::new(),
                     _boo: PhantomData,
                 }
             }
         } else {
             // We're at the ghost, just replace our list with an empty one.
             // No other state needs to be changed.
-            std::mem::replace(self.list, LinkedList::new())
+            self.list.take_nodes()
         }
     }
 
-    pub fn split_after(&mut self) -> LinkedList<T> {
+    pub fn split_after(&mut self) -> LinkedList<T, A>
+    where
+        A: Clone,
+    {
         // We have this:
         //
         //     list.front -> A <-> B <-> C <-> D <- list.back
@@ -609,9 +1392,10 @@ impl<'a, T> CursorMut<'a, T> {
                 let new_idx = Some(old_idx);
 
                 // What the output will become
+                // cur就是back时输出为空，back也得是None
                 let output_len = old_len - new_len;
                 let output_front = next;
-                let output_back = self.list.back;
+                let output_back = next.and(self.list.back);
 
                 // Break the links between cur and next
                 if let Some(next) = next {
@@ -624,22 +1408,30 @@ impl<'a, T> CursorMut<'a, T> {
                 self.list.front = new_front;
                 self.list.back = new_back;
                 self.index = new_idx;
+                // 分出去的节点上的句柄作废
+                self.list.handles.release_chain(output_front, output_len);
 
                 LinkedList {
                     front: output_front,
                     back: output_back,
                     len: output_len,
+                    alloc: self.list.alloc.clone(),
+                    cache: None,
+                    cache_len: 0,
+                    cache_max: 0,
+                    handles: Handles::new(),
                     _boo: PhantomData,
                 }
             }
         } else {
             // We're at the ghost, just replace our list with an empty one.
             // No other state needs to be changed.
-            std::mem::replace(self.list, LinkedList::new())
+            self.list.take_nodes()
         }
     }
 
-    pub fn splice_before(&mut self, mut input: LinkedList<T>) {
+    pub fn splice_before(&mut self, mut input: LinkedList<T, A>) {
+        assert!(self.list.alloc.same_as(&input.alloc), "Cannot splice lists with different allocators");
         // We have this:
         //
         // input.front -> 1 <-> 2 <- input.back
@@ -690,7 +1482,9 @@ impl<'a, T> CursorMut<'a, T> {
                 self.list.back = Some(in_back);
             } else {
                 // We're empty, become the input, remain on the ghost
-                std::mem::swap(self.list, &mut input);
+                // 只接管节点，len在下面加
+                self.list.front = input.front.take();
+                self.list.back = input.back.take();
             }
 
             self.list.len += input.len;
@@ -701,7 +1495,8 @@ impl<'a, T> CursorMut<'a, T> {
         }
     }
 
-    pub fn splice_after(&mut self, mut input: LinkedList<T>) {
+    pub fn splice_after(&mut self, mut input: LinkedList<T, A>) {
+        assert!(self.list.alloc.same_as(&input.alloc), "Cannot splice lists with different allocators");
         // We have this:
         //
         // input.front -> 1 <-> 2 <- input.back
@@ -751,7 +1546,9 @@ impl<'a, T> CursorMut<'a, T> {
                 self.list.front = Some(in_front);
             } else {
                 // We're empty, become the input, remain on the ghost
-                std::mem::swap(self.list, &mut input);
+                // 只接管节点，len在下面加
+                self.list.front = input.front.take();
+                self.list.back = input.back.take();
             }
 
             self.list.len += input.len;
@@ -762,16 +1559,122 @@ impl<'a, T> CursorMut<'a, T> {
         }
     }
 }
-// ================================================
-impl<T> Default for LinkedList<T> {
-    fn default() -> Self {
-        Self::new()
-    }
+
+// 只读光标 =======================================
+// 返回的引用是'a的，不受光标本身借用的约束
+impl<'a, T, A: NodeAllocator> Cursor<'a, T, A> {
+    pub fn index(&self) -> Option<usize> {
+        self.index
+    }
+
+    pub fn move_next(&mut self) {
+        if let Some(cur) = self.cur {
+            unsafe {
+                self.cur = (*cur.as_ptr()).back;
+                if self.cur.is_some() {
+                    *self.index.as_mut().unwrap() += 1;
+                } else {
+                    self.index = None;
+                }
+            }
+        } else if !self.list.is_empty() {
+            self.cur = self.list.front;
+            self.index = Some(0);
+        }
+    }
+
+    pub fn move_prev(&mut self) {
+        if let Some(cur) = self.cur {
+            unsafe {
+                self.cur = (*cur.as_ptr()).front;
+                if self.cur.is_some() {
+                    *self.index.as_mut().unwrap() -= 1;
+                } else {
+                    self.index = None;
+                }
+            }
+        } else if !self.list.is_empty() {
+            self.cur = self.list.back;
+            self.index = Some(self.list.len - 1);
+        }
+    }
+
+    pub fn current(&self) -> Option<&'a T> {
+        unsafe { self.cur.map(|node| &(*node.as_ptr()).elem) }
+    }
+
+    pub fn peek_next(&self) -> Option<&'a T> {
+        unsafe {
+            let next = if let Some(cur) = self.cur {
+                (*cur.as_ptr()).back
+            } else {
+                self.list.front
+            };
+            next.map(|node| &(*node.as_ptr()).elem)
+        }
+    }
+
+    pub fn peek_prev(&self) -> Option<&'a T> {
+        unsafe {
+            let prev = if let Some(cur) = self.cur {
+                (*cur.as_ptr()).front
+            } else {
+                self.list.back
+            };
+            prev.map(|node| &(*node.as_ptr()).elem)
+        }
+    }
+
+    // 从当前位置往back方向迭代，包含当前元素
+    // 在ghost上时，ghost的下一个是front，所以迭代整个链表
+    pub fn iter_forward(&self) -> Iter<'a, T> {
+        match self.index {
+            Some(index) => Iter {
+                front: self.cur,
+                back: self.list.back,
+                len: self.list.len - index,
+                _boo: PhantomData,
+            },
+            None => self.list.into_iter(),
+        }
+    }
+
+    // 从当前位置往front方向迭代，包含当前元素
+    // 在ghost上时，ghost的上一个是back，所以倒着迭代整个链表
+    pub fn iter_backward(&self) -> core::iter::Rev<Iter<'a, T>> {
+        match self.index {
+            Some(index) => Iter {
+                front: self.list.front,
+                back: self.cur,
+                len: index + 1,
+                _boo: PhantomData,
+            }.rev(),
+            None => self.list.into_iter().rev(),
+        }
+    }
 }
 
-impl<T: Clone> Clone for LinkedList<T> {
+// derive会要求T: Clone，这里只是复制指针
+impl<'a, T, A: NodeAllocator> Clone for Cursor<'a, T, A> {
     fn clone(&self) -> Self {
-        let mut new_list = Self::new();
+        Cursor {
+            list: self.list,
+            cur: self.cur,
+            index: self.index,
+        }
+    }
+}
+// ================================================
+impl<T, A: NodeAllocator + Default> Default for LinkedList<T, A> {
+    fn default() -> Self {
+        Self::new_in(A::default())
+    }
+}
+
+impl<T: Clone, A: NodeAllocator + Clone> Clone for LinkedList<T, A> {
+    fn clone(&self) -> Self {
+        // 缓存的设置也带上，但缓存的节点不复制
+        let mut new_list = Self::with_node_cache_in(self.cache_max, self.alloc.clone());
         for item in self {
             new_list.push_back(item.clone());
         }
@@ -780,22 +1683,22 @@ impl<T: Clone> Clone for LinkedList<T> {
 }
 // 延长
 // 将一个带所有权的迭代器的所有元素接到后面
-impl<T> Extend<T> for LinkedList<T> {
+impl<T, A: NodeAllocator> Extend<T> for LinkedList<T, A> {
     fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
         for item in iter {
             self.push_back(item);
         }
     }
 }
-impl<T> FromIterator<T> for LinkedList<T> {
+impl<T, A: NodeAllocator + Default> FromIterator<T> for LinkedList<T, A> {
     fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
-        let mut list = Self::new();
+        let mut list = Self::default();
         list.extend(iter);
         list
     }
 }
 
-impl<T: Debug> Debug for LinkedList<T> {
+impl<T: Debug, A: NodeAllocator> Debug for LinkedList<T, A> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_list().entries(self).finish()
     }
@@ -813,23 +1716,19 @@ impl<T: Debug> Debug for LinkedList<T> {
 //   impl PartialEq<Book> for BookFormat
 // assert_eq!是断言PartialEq的
 // 实现必须保证eq和ne互斥，分开只是为了允许优化算法，并不是允许你实现不互斥的结果
-impl<T: PartialEq> PartialEq for LinkedList<T> {
+impl<T: PartialEq, A: NodeAllocator> PartialEq for LinkedList<T, A> {
     fn eq(&self, other: &Self) -> bool {
         self.len() == other.len() && self.into_iter().eq(other)
     }
-
-    fn ne(&self, other: &Self) -> bool {
-        self.len() != other.len() || self.into_iter().ne(other)
-    }
 }
 
 // 实现等价关系，要求满足自反性（reflexive）、对称性、传递性
-impl<T: Eq> Eq for LinkedList<T> {}
+impl<T: Eq, A: NodeAllocator> Eq for LinkedList<T, A> {}
 
 // PartialOrd实现偏序关系，满足反对称性、自反性、传递性
 // 实现<、<=、>、>=，顺带满足PartialEq所以实现了==和!=
 // 因为没有完全性，所以两者可能不可比，所以返回值是Option
-impl<T: PartialOrd> PartialOrd for LinkedList<T> {
+impl<T: PartialOrd, A: NodeAllocator> PartialOrd for LinkedList<T, A> {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
         self.into_iter().partial_cmp(other)
     }
@@ -838,7 +1737,7 @@ impl<T: PartialOrd> PartialOrd for LinkedList<T> {
 // 实现全序关系，要求满足反对称性、传递性、完全性
 // 严格无自反，自反无严格
 // 完全性指集合中任意两个元素都可比较
-impl<T: Ord> Ord for LinkedList<T> {
+impl<T: Ord, A: NodeAllocator> Ord for LinkedList<T, A> {
     fn cmp(&self, other: &Self) -> Ordering {
         self.into_iter().cmp(other)
     }
@@ -846,7 +1745,7 @@ impl<T: Ord> Ord for LinkedList<T> {
 
 // Hash
 // 等学到Hash之后再回来看
-impl<T: Hash> Hash for LinkedList<T> {
+impl<T: Hash, A: NodeAllocator> Hash for LinkedList<T, A> {
     fn hash<H: Hasher>(&self, state: &mut H) {
         self.len().hash(state);
         for item in self {
@@ -857,8 +1756,8 @@ impl<T: Hash> Hash for LinkedList<T> {
 
 // 实现了这个就全自动实现了？
 // 默认实现代表的是对整个对象上锁？
-unsafe impl<T: Send> Send for LinkedList<T> {}
-unsafe impl<T: Sync> Sync for LinkedList<T> {}
+unsafe impl<T: Send, A: NodeAllocator + Send> Send for LinkedList<T, A> {}
+unsafe impl<T: Sync, A: NodeAllocator + Sync> Sync for LinkedList<T, A> {}
 
 unsafe impl<'a, T: Send> Send for Iter<'a, T> {}
 unsafe impl<'a, T: Sync> Sync for Iter<'a, T> {}
@@ -866,8 +1765,62 @@ unsafe impl<'a, T: Sync> Sync for Iter<'a, T> {}
 unsafe impl<'a, T: Send> Send for IterMut<'a, T> {}
 unsafe impl<'a, T: Sync> Sync for IterMut<'a, T> {}
 
+// Cursor只给出&T，和&LinkedList一样
+unsafe impl<'a, T: Sync, A: NodeAllocator + Sync> Send for Cursor<'a, T, A> {}
+unsafe impl<'a, T: Sync, A: NodeAllocator + Sync> Sync for Cursor<'a, T, A> {}
+
+// 通用接口 =======================================
+// 栈从front进出，队列从back进front出
+impl<T, A: NodeAllocator> crate::Stack for LinkedList<T, A> {
+    type Item = T;
+
+    fn push(&mut self, elem: T) {
+        self.push_front(elem)
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        self.pop_front()
+    }
+}
+
+impl<T, A: NodeAllocator> crate::Queue for LinkedList<T, A> {
+    type Item = T;
+
+    fn enqueue(&mut self, elem: T) {
+        self.push_back(elem)
+    }
+
+    fn dequeue(&mut self) -> Option<T> {
+        self.pop_front()
+    }
+}
+
+impl<T, A: NodeAllocator> crate::Deque for LinkedList<T, A> {
+    type Item = T;
+
+    fn push_front(&mut self, elem: T) {
+        self.push_front(elem)
+    }
+
+    fn push_back(&mut self, elem: T) {
+        self.push_back(elem)
+    }
+
+    fn pop_front(&mut self) -> Option<T> {
+        self.pop_front()
+    }
+
+    fn pop_back(&mut self) -> Option<T> {
+        self.pop_back()
+    }
+}
+
 #[cfg(test)]
 mod test {
+    crate::stack_conformance!(stack, super::LinkedList<i32>);
+    crate::queue_conformance!(queue, super::LinkedList<i32>);
+    crate::deque_conformance!(deque, super::LinkedList<i32>);
+
     // ======================================
     // 概念：子类型（Subtyping）
     // 一个&'big T是&'small T的子类型（其中'big代表比'small更长的生命周期）
@@ -887,7 +1840,8 @@ mod test {
     // 这里就有
     // 因为&mut T是T的不变，&Cell<T> 就是 &mut T，所以&Cell<T>是T的不变
     //========================================
-    use super::LinkedList;
+    use super::{ Global, LinkedList, NodeAllocator };
+    use std::{ alloc::Layout, ptr::NonNull };
 
     fn generate_test() -> LinkedList<i32> {
         list_from(&[0, 1, 2, 3, 4, 5, 6])
@@ -1020,7 +1974,7 @@ mod test {
             assert_eq!(6 - (i as i32), *elt);
         }
         let mut n = LinkedList::new();
-        assert_eq!((&n).into_iter().rev().next(), None);
+        assert_eq!((&n).into_iter().next_back(), None);
         n.push_front(4);
         let mut it = (&n).into_iter().rev();
         assert_eq!(it.size_hint(), (1, Some(1)));
@@ -1094,8 +2048,9 @@ mod test {
     }
 
     #[test]
+    #[allow(clippy::neg_cmp_op_on_partial_ord)]
     fn test_ord_nan() {
-        let nan = 0.0f64 / 0.0;
+        let nan = f64::NAN;
         let n = list_from(&[nan]);
         let m = list_from(&[nan]);
         assert!(!(n < m));
@@ -1130,7 +2085,7 @@ mod test {
         let list: LinkedList<i32> = (0..10).collect();
         assert_eq!(format!("{:?}", list), "[0, 1, 2, 3, 4, 5, 6, 7, 8, 9]");
 
-        let list: LinkedList<&str> = vec!["just", "one", "test", "more"].iter().copied().collect();
+        let list: LinkedList<&str> = ["just", "one", "test", "more"].iter().copied().collect();
         assert_eq!(format!("{:?}", list), r#"["just", "one", "test", "more"]"#);
     }
 
@@ -1215,7 +2170,6 @@ mod test {
         check_links(&m);
         assert_eq!((&m).into_iter().cloned().collect::<Vec<_>>(), &[10, 7, 1, 8, 2, 3, 4, 5, 6, 9]);
 
-        /* remove_current not impl'd
         let mut cursor = m.cursor_mut();
         cursor.move_next();
         cursor.move_prev();
@@ -1230,8 +2184,7 @@ mod test {
         cursor.move_next();
         assert_eq!(cursor.remove_current(), Some(10));
         check_links(&m);
-        assert_eq!(m.iter().cloned().collect::<Vec<_>>(), &[1, 8, 2, 3, 4, 5, 6]);
-        */
+        assert_eq!((&m).into_iter().cloned().collect::<Vec<_>>(), &[1, 8, 2, 3, 4, 5, 6]);
 
         let mut m: LinkedList<u32> = LinkedList::new();
         m.extend([1, 8, 2, 3, 4, 5, 6]);
@@ -1271,7 +2224,685 @@ mod test {
         );
     }
 
-    fn check_links<T: Eq + std::fmt::Debug>(list: &LinkedList<T>) {
+    #[test]
+    fn test_cursor_insert_remove() {
+        let mut m: LinkedList<u32> = LinkedList::new();
+        m.extend([1, 2, 3, 4, 5, 6]);
+        let mut cursor = m.cursor_mut();
+        // ghost上：insert_after插到front，insert_before插到back
+        cursor.insert_after(0);
+        cursor.insert_before(7);
+        assert_eq!(cursor.index(), None);
+        cursor.move_next();
+        cursor.move_next();
+        assert_eq!(cursor.current(), Some(&mut 1));
+        cursor.insert_before(10);
+        assert_eq!(cursor.index(), Some(2));
+        cursor.insert_after(11);
+        assert_eq!(cursor.index(), Some(2));
+        assert_eq!(cursor.peek_prev(), Some(&mut 10));
+        assert_eq!(cursor.peek_next(), Some(&mut 11));
+        check_links(&m);
+        assert_eq!(m.len(), 10);
+        assert_eq!(
+            (&m).into_iter().cloned().collect::<Vec<_>>(),
+            &[0, 10, 1, 11, 2, 3, 4, 5, 6, 7]
+        );
+
+        let mut cursor = m.cursor_mut();
+        cursor.move_prev();
+        assert_eq!(cursor.replace_current(70), Ok(7));
+        cursor.insert_after(8);
+        assert_eq!(cursor.index(), Some(9));
+        cursor.move_next();
+        assert_eq!(cursor.remove_current(), Some(8));
+        assert_eq!(cursor.index(), None);
+        assert_eq!(cursor.replace_current(9), Err(9));
+        cursor.move_next();
+        assert_eq!(cursor.remove_current(), Some(0));
+        assert_eq!(cursor.index(), Some(0));
+        assert_eq!(cursor.current(), Some(&mut 10));
+        let single = cursor.remove_current_as_list().unwrap();
+        assert_eq!(single.len(), 1);
+        check_links(&single);
+        assert_eq!(single.into_iter().collect::<Vec<_>>(), &[10]);
+        assert_eq!(cursor.current(), Some(&mut 1));
+        assert_eq!(cursor.index(), Some(0));
+        check_links(&m);
+        assert_eq!(m.len(), 8);
+        assert_eq!((&m).into_iter().cloned().collect::<Vec<_>>(), &[1, 11, 2, 3, 4, 5, 6, 70]);
+
+        // 单元素
+        let mut m: LinkedList<u32> = LinkedList::new();
+        let mut cursor = m.cursor_mut();
+        assert_eq!(cursor.remove_current(), None);
+        assert!(cursor.remove_current_as_list().is_none());
+        cursor.insert_before(1);
+        cursor.move_next();
+        assert_eq!(cursor.index(), Some(0));
+        assert_eq!(cursor.remove_current(), Some(1));
+        assert_eq!(cursor.index(), None);
+        assert_eq!(cursor.current(), None);
+        assert!(m.is_empty());
+        assert_eq!(m.front(), None);
+        assert_eq!(m.back(), None);
+        m.push_back(2);
+        check_links(&m);
+        assert_eq!(m.len(), 1);
+    }
+
+    #[test]
+    fn test_append_prepend() {
+        let mut m: LinkedList<u32> = (1..=3).collect();
+        let mut n: LinkedList<u32> = (4..=6).collect();
+        m.append(&mut n);
+        check_links(&m);
+        assert_eq!(m.len(), 6);
+        assert!(n.is_empty());
+        assert_eq!((&m).into_iter().cloned().collect::<Vec<_>>(), &[1, 2, 3, 4, 5, 6]);
+
+        // 两边各自为空
+        m.append(&mut n);
+        assert_eq!(m.len(), 6);
+        n.append(&mut m);
+        assert!(m.is_empty());
+        assert_eq!(n.len(), 6);
+        n.push_back(7);
+        m.push_back(0);
+        check_links(&n);
+        check_links(&m);
+
+        let mut p: LinkedList<u32> = (10..=11).collect();
+        n.prepend(&mut p);
+        assert!(p.is_empty());
+        n.prepend(&mut p);
+        n.prepend(&mut m);
+        assert!(m.is_empty());
+        check_links(&n);
+        assert_eq!(n.len(), 10);
+        assert_eq!(
+            (&n).into_iter().cloned().collect::<Vec<_>>(),
+            &[0, 10, 11, 1, 2, 3, 4, 5, 6, 7]
+        );
+        m.prepend(&mut n);
+        assert_eq!(m.len(), 10);
+        assert_eq!(m.front(), Some(&0));
+        assert_eq!(m.back(), Some(&7));
+    }
+
+    #[test]
+    fn test_split_off() {
+        for at in 0..=7u32 {
+            let mut m: LinkedList<u32> = (0..7).collect();
+            let tail = m.split_off(at as usize);
+            check_links(&m);
+            check_links(&tail);
+            assert_eq!((&m).into_iter().cloned().collect::<Vec<_>>(), (0..at).collect::<Vec<_>>());
+            assert_eq!((&tail).into_iter().cloned().collect::<Vec<_>>(), (at..7).collect::<Vec<_>>());
+            assert_eq!(m.len() + tail.len(), 7);
+        }
+
+        // 分出来的空链表要能正常用
+        let mut m: LinkedList<u32> = (0..3).collect();
+        let mut tail = m.split_off(3);
+        tail.push_back(10);
+        tail.push_front(9);
+        m.push_back(3);
+        check_links(&m);
+        check_links(&tail);
+        assert_eq!((&m).into_iter().cloned().collect::<Vec<_>>(), &[0, 1, 2, 3]);
+        assert_eq!((&tail).into_iter().cloned().collect::<Vec<_>>(), &[9, 10]);
+
+        let mut cursor = m.cursor_mut();
+        cursor.move_next();
+        let mut front = cursor.split_before();
+        front.push_back(100);
+        assert_eq!(front.into_iter().collect::<Vec<_>>(), &[100]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_split_off_out_of_bounds() {
+        let mut m: LinkedList<u32> = (0..3).collect();
+        m.split_off(4);
+    }
+
+    #[test]
+    fn test_sort() {
+        let mut m: LinkedList<i32> = LinkedList::new();
+        m.sort();
+        assert!(m.is_empty());
+        assert!(m.is_sorted_by(|a, b| a <= b));
+
+        m.push_back(1);
+        m.sort();
+        assert_eq!((&m).into_iter().cloned().collect::<Vec<_>>(), &[1]);
+
+        for len in [2, 3, 7, 16, 17, 100] {
+            // 简单的伪随机，别引入依赖
+            let mut seed = len as u32;
+            let v: Vec<i32> = (0..len)
+                .map(|_| {
+                    seed = seed.wrapping_mul(1103515245).wrapping_add(12345);
+                    ((seed >> 16) % 50) as i32
+                })
+                .collect();
+            let mut m: LinkedList<i32> = v.iter().cloned().collect();
+            let addrs: Vec<*const i32> = (&m).into_iter().map(|x| x as *const i32).collect();
+            m.sort();
+            check_links(&m);
+            assert_eq!(m.len(), len);
+            assert!(m.is_sorted_by(|a, b| a <= b));
+
+            let mut expected = v.clone();
+            expected.sort();
+            assert_eq!((&m).into_iter().cloned().collect::<Vec<_>>(), expected);
+
+            // 元素没被搬动过，只是节点换了位置
+            let mut before = addrs;
+            let mut after: Vec<*const i32> = (&m).into_iter().map(|x| x as *const i32).collect();
+            before.sort();
+            after.sort();
+            assert_eq!(before, after);
+
+            m.sort_by(|a, b| b.cmp(a));
+            assert!(m.is_sorted_by(|a, b| a >= b));
+            assert_eq!(m.front(), expected.last());
+            assert_eq!(m.back(), expected.first());
+            m.push_back(-1);
+            m.push_front(100);
+            check_links(&m);
+        }
+    }
+
+    #[test]
+    fn test_sort_stable() {
+        let v = [(3, 'a'), (1, 'b'), (3, 'c'), (2, 'd'), (1, 'e'), (3, 'f'), (2, 'g')];
+        let mut m: LinkedList<(i32, char)> = v.iter().cloned().collect();
+        m.sort_by_key(|&(k, _)| k);
+        check_links(&m);
+        assert_eq!(
+            (&m).into_iter().cloned().collect::<Vec<_>>(),
+            &[(1, 'b'), (1, 'e'), (2, 'd'), (2, 'g'), (3, 'a'), (3, 'c'), (3, 'f')]
+        );
+        assert!(!m.is_sorted_by(|a, b| a.1 <= b.1));
+    }
+
+    #[test]
+    fn test_sort_panic_safe() {
+        use std::panic::{ catch_unwind, AssertUnwindSafe };
+
+        for limit in [0, 1, 5, 15, 25] {
+            let v: Vec<u32> = (0..20).rev().collect();
+            let mut m: LinkedList<u32> = v.iter().cloned().collect();
+            let mut count = 0;
+            let result = catch_unwind(
+                AssertUnwindSafe(|| {
+                    m.sort_by(|a, b| {
+                        count += 1;
+                        if count > limit {
+                            panic!("compare panicked");
+                        }
+                        a.cmp(b)
+                    })
+                })
+            );
+            assert!(result.is_err());
+
+            // 链表仍然完整，元素一个不少
+            check_links(&m);
+            assert_eq!(m.len(), 20);
+            let mut elems: Vec<u32> = (&m).into_iter().cloned().collect();
+            elems.sort();
+            assert_eq!(elems, (0..20).collect::<Vec<_>>());
+            assert_eq!((&m).into_iter().rev().count(), 20);
+
+            m.sort();
+            assert_eq!((&m).into_iter().cloned().collect::<Vec<_>>(), (0..20).collect::<Vec<_>>());
+        }
+    }
+
+    #[test]
+    fn test_retain() {
+        let mut m: LinkedList<u32> = (0..10).collect();
+        m.retain(|&x| x % 3 != 0);
+        check_links(&m);
+        assert_eq!(m.len(), 6);
+        assert_eq!((&m).into_iter().cloned().collect::<Vec<_>>(), &[1, 2, 4, 5, 7, 8]);
+
+        m.retain_mut(|x| {
+            *x *= 10;
+            *x > 20
+        });
+        check_links(&m);
+        assert_eq!((&m).into_iter().cloned().collect::<Vec<_>>(), &[40, 50, 70, 80]);
+
+        m.retain(|_| false);
+        assert!(m.is_empty());
+        assert_eq!(m.front(), None);
+        assert_eq!(m.back(), None);
+        m.push_back(1);
+        check_links(&m);
+    }
+
+    #[test]
+    fn test_extract_if() {
+        let mut m: LinkedList<u32> = (0..10).collect();
+        let evens: Vec<u32> = m.extract_if(|x| *x % 2 == 0).collect();
+        assert_eq!(evens, &[0, 2, 4, 6, 8]);
+        check_links(&m);
+        assert_eq!(m.len(), 5);
+        assert_eq!((&m).into_iter().cloned().collect::<Vec<_>>(), &[1, 3, 5, 7, 9]);
+
+        // 中途drop，剩下的原样保留
+        {
+            let mut iter = m.extract_if(|x| *x > 2);
+            assert_eq!(iter.size_hint(), (0, Some(5)));
+            assert_eq!(iter.next(), Some(3));
+            assert_eq!(iter.size_hint(), (0, Some(3)));
+        }
+        check_links(&m);
+        assert_eq!(m.len(), 4);
+        assert_eq!((&m).into_iter().cloned().collect::<Vec<_>>(), &[1, 5, 7, 9]);
+
+        let mut empty: LinkedList<u32> = LinkedList::new();
+        assert_eq!(empty.extract_if(|_| true).next(), None);
+    }
+
+    #[test]
+    fn test_extract_if_panic_safe() {
+        use std::panic::{ catch_unwind, AssertUnwindSafe };
+
+        let mut m: LinkedList<u32> = (0..10).collect();
+        let result = catch_unwind(
+            AssertUnwindSafe(|| {
+                m.retain(|&x| {
+                    if x == 6 {
+                        panic!("pred panicked");
+                    }
+                    x % 2 == 1
+                })
+            })
+        );
+        assert!(result.is_err());
+        check_links(&m);
+        assert_eq!(m.len(), 7);
+        assert_eq!((&m).into_iter().cloned().collect::<Vec<_>>(), &[1, 3, 5, 6, 7, 8, 9]);
+        assert_eq!((&m).into_iter().rev().count(), 7);
+    }
+
+    // 数分配和释放次数的分配器，借用着用
+    #[derive(Default)]
+    struct Counting {
+        allocs: std::cell::Cell<usize>,
+        frees: std::cell::Cell<usize>,
+    }
+
+    unsafe impl NodeAllocator for Counting {
+        fn allocate(&self, layout: Layout) -> NonNull<u8> {
+            self.allocs.set(self.allocs.get() + 1);
+            Global.allocate(layout)
+        }
+
+        unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+            self.frees.set(self.frees.get() + 1);
+            Global.deallocate(ptr, layout)
+        }
+
+        fn same_as(&self, other: &Self) -> bool {
+            std::ptr::eq(self, other)
+        }
+    }
+
+    // 只分配不释放的arena，整块一起还
+    struct Bump {
+        chunk: NonNull<u8>,
+        used: std::cell::Cell<usize>,
+    }
+
+    impl Bump {
+        const LAYOUT: Layout = match Layout::from_size_align(4096, 16) {
+            Ok(layout) => layout,
+            Err(_) => panic!(),
+        };
+
+        fn new() -> Self {
+            Bump {
+                chunk: Global.allocate(Self::LAYOUT),
+                used: std::cell::Cell::new(0),
+            }
+        }
+    }
+
+    impl Drop for Bump {
+        fn drop(&mut self) {
+            unsafe { Global.deallocate(self.chunk, Self::LAYOUT) }
+        }
+    }
+
+    unsafe impl NodeAllocator for Bump {
+        fn allocate(&self, layout: Layout) -> NonNull<u8> {
+            let start = self.used.get().next_multiple_of(layout.align());
+            let end = start + layout.size();
+            assert!(end <= Self::LAYOUT.size(), "bump arena exhausted");
+            self.used.set(end);
+            unsafe { NonNull::new_unchecked(self.chunk.as_ptr().add(start)) }
+        }
+
+        unsafe fn deallocate(&self, _ptr: NonNull<u8>, _layout: Layout) {}
+
+        fn same_as(&self, other: &Self) -> bool {
+            std::ptr::eq(self, other)
+        }
+    }
+
+    #[test]
+    fn test_allocator() {
+        let counting = Counting::default();
+        {
+            let mut m = LinkedList::new_in(&counting);
+            m.extend(0..10);
+            assert_eq!(counting.allocs.get(), 10);
+            assert_eq!(m.pop_front(), Some(0));
+            assert_eq!(m.pop_back(), Some(9));
+            assert_eq!(counting.frees.get(), 2);
+
+            let mut cursor = m.cursor_mut();
+            cursor.move_next();
+            cursor.insert_before(-1);
+            cursor.insert_after(-2);
+            assert_eq!(cursor.remove_current(), Some(1));
+            let tail = cursor.split_after();
+            let mut n = LinkedList::new_in(&counting);
+            n.push_back(100);
+            cursor.splice_after(n);
+            m.retain(|&x| x != 100);
+            let mut tail = tail;
+            m.append(&mut tail);
+            m.sort();
+            check_links(&m);
+            assert_eq!((&m).into_iter().cloned().collect::<Vec<_>>(), &[-2, -1, 2, 3, 4, 5, 6, 7, 8]);
+            let cloned = m.clone();
+            assert!(cloned.allocator().same_as(m.allocator()));
+            let back = m.split_off(5);
+            assert_eq!(back.len(), 4);
+        }
+        assert_eq!(counting.allocs.get(), 22);
+        assert_eq!(counting.allocs.get(), counting.frees.get());
+    }
+
+    #[test]
+    fn test_bump_allocator() {
+        let bump = Bump::new();
+        let mut m = LinkedList::new_in(&bump);
+        m.extend(0..20u64);
+        let mut n = LinkedList::new_in(&bump);
+        n.extend(20..40u64);
+        m.append(&mut n);
+        m.sort_by(|a, b| b.cmp(a));
+        check_links(&m);
+        assert_eq!((&m).into_iter().cloned().collect::<Vec<_>>(), (0..40).rev().collect::<Vec<_>>());
+        assert!(bump.used.get() > 0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_splice_different_allocator() {
+        let a = Counting::default();
+        let b = Counting::default();
+        let mut m = LinkedList::new_in(&a);
+        m.push_back(1);
+        let mut n = LinkedList::new_in(&b);
+        n.push_back(2);
+        m.cursor_mut().splice_after(n);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_append_different_allocator() {
+        let a = Counting::default();
+        let b = Counting::default();
+        let mut m = LinkedList::new_in(&a);
+        let mut n = LinkedList::new_in(&b);
+        n.push_back(2);
+        m.append(&mut n);
+    }
+
+    #[test]
+    fn test_node_cache() {
+        let counting = Counting::default();
+        {
+            let mut m = LinkedList::with_node_cache_in(4, &counting);
+            m.extend(0..8);
+            assert_eq!(counting.allocs.get(), 8);
+
+            // 队列用法：一进一出，全部复用缓存里的节点
+            for i in 8..1000 {
+                m.push_back(i);
+                assert_eq!(m.pop_front(), Some(i - 8));
+            }
+            assert_eq!(counting.allocs.get(), 9);
+            assert_eq!(counting.frees.get(), 0);
+            assert_eq!(m.node_cache_len(), 1);
+
+            // 缓存满了之后才真正释放
+            for _ in 0..6 {
+                m.pop_back();
+            }
+            assert_eq!(m.node_cache_len(), 4);
+            assert_eq!(counting.frees.get(), 3);
+            check_links(&m);
+            assert_eq!((&m).into_iter().cloned().collect::<Vec<_>>(), &[992, 993]);
+
+            m.shrink_node_cache();
+            assert_eq!(m.node_cache_len(), 0);
+            assert_eq!(counting.frees.get(), 7);
+
+            // 其它摘节点的路径也走缓存
+            m.extend(0..4);
+            m.retain(|&x| x % 2 == 0);
+            let mut cursor = m.cursor_mut();
+            cursor.move_next();
+            assert_eq!(cursor.remove_current(), Some(992));
+            assert_eq!(m.node_cache_len(), 4);
+
+            // 从空的m上append/split，缓存还是自己的
+            let mut other = LinkedList::new_in(&counting);
+            other.push_back(100);
+            let mut taken = m.split_off(0);
+            assert_eq!(m.node_cache_len(), 4);
+            m.append(&mut other);
+            assert_eq!(m.node_cache_len(), 4);
+            assert_eq!(other.node_cache_len(), 0);
+            taken.prepend(&mut m);
+            check_links(&taken);
+            assert_eq!((&taken).into_iter().cloned().collect::<Vec<_>>(), &[100, 0, 2]);
+            m.push_back(1);
+            assert_eq!(m.node_cache_len(), 3);
+
+            m.clear();
+            assert!(m.is_empty());
+            assert_eq!(m.node_cache_len(), 0);
+            m.push_back(7);
+        }
+        assert_eq!(counting.allocs.get(), counting.frees.get());
+    }
+
+    #[test]
+    fn test_cursor_positioned() {
+        let mut m: LinkedList<u32> = (0..10).collect();
+        assert_eq!(m.cursor_front_mut().current(), Some(&mut 0));
+        assert_eq!(m.cursor_front_mut().index(), Some(0));
+        assert_eq!(m.cursor_back_mut().current(), Some(&mut 9));
+        assert_eq!(m.cursor_back_mut().index(), Some(9));
+        for i in 0..10 {
+            let mut cursor = m.cursor_at_mut(i);
+            assert_eq!(cursor.index(), Some(i));
+            assert_eq!(cursor.current(), Some(&mut (i as u32)));
+        }
+        let mut cursor = m.cursor_at_mut(10);
+        assert_eq!(cursor.index(), None);
+        assert_eq!(cursor.current(), None);
+        cursor.insert_before(10);
+        check_links(&m);
+        assert_eq!(m.back(), Some(&10));
+
+        let mut empty: LinkedList<u32> = LinkedList::new();
+        assert_eq!(empty.cursor_front_mut().index(), None);
+        assert_eq!(empty.cursor_back_mut().index(), None);
+        assert_eq!(empty.cursor_at_mut(0).index(), None);
+        let mut cursor = empty.cursor_at_mut(0);
+        cursor.seek(0);
+        cursor.move_by(5);
+        cursor.move_by(-3);
+        assert_eq!(cursor.current(), None);
+    }
+
+    #[test]
+    fn test_cursor_seek_move_by() {
+        let mut m: LinkedList<u32> = (0..10).collect();
+        let mut cursor = m.cursor_mut();
+        // 各种起点和方向都试一遍
+        for &i in &[3, 4, 8, 1, 0, 9, 10, 5, 6, 2, 10, 7] {
+            cursor.seek(i);
+            if i == 10 {
+                assert_eq!(cursor.index(), None);
+                assert_eq!(cursor.current(), None);
+            } else {
+                assert_eq!(cursor.index(), Some(i));
+                assert_eq!(cursor.current(), Some(&mut (i as u32)));
+            }
+        }
+
+        cursor.seek(2);
+        cursor.move_by(3);
+        assert_eq!(cursor.current(), Some(&mut 5));
+        cursor.move_by(-5);
+        assert_eq!(cursor.current(), Some(&mut 0));
+        cursor.move_by(-1);
+        assert_eq!(cursor.index(), None);
+        cursor.move_by(-1);
+        assert_eq!(cursor.current(), Some(&mut 9));
+        cursor.move_by(2);
+        assert_eq!(cursor.current(), Some(&mut 0));
+        // 绕一整圈（11个位置）回到原地
+        cursor.move_by(11);
+        assert_eq!(cursor.current(), Some(&mut 0));
+        cursor.move_by(-11 * 3 + 4);
+        assert_eq!(cursor.current(), Some(&mut 4));
+        cursor.move_by(0);
+        assert_eq!(cursor.index(), Some(4));
+
+        // 和一步步走的结果一样
+        let mut other: LinkedList<u32> = (0..10).collect();
+        for n in -25..25isize {
+            let mut a = m.cursor_at_mut(3);
+            a.move_by(n);
+            let a = a.index();
+            let mut b = other.cursor_at_mut(3);
+            for _ in 0..n.unsigned_abs() {
+                if n > 0 {
+                    b.move_next();
+                } else {
+                    b.move_prev();
+                }
+            }
+            assert_eq!(a, b.index());
+        }
+        other.cursor_at_mut(7).remove_current();
+        assert_eq!(other.len(), 9);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_cursor_seek_out_of_bounds() {
+        let mut m: LinkedList<u32> = (0..10).collect();
+        m.cursor_mut().seek(11);
+    }
+
+    #[test]
+    fn test_cursor_move_peek_ref() {
+        let m: LinkedList<u32> = (1..=6).collect();
+        let mut cursor = m.cursor_front();
+        assert_eq!(cursor.current(), Some(&1));
+        assert_eq!(cursor.peek_next(), Some(&2));
+        assert_eq!(cursor.peek_prev(), None);
+        assert_eq!(cursor.index(), Some(0));
+        cursor.move_prev();
+        assert_eq!(cursor.current(), None);
+        assert_eq!(cursor.peek_next(), Some(&1));
+        assert_eq!(cursor.peek_prev(), Some(&6));
+        assert_eq!(cursor.index(), None);
+        cursor.move_next();
+        cursor.move_next();
+        assert_eq!(cursor.current(), Some(&2));
+        assert_eq!(cursor.index(), Some(1));
+
+        // 引用活得比光标久，而且能和别的只读借用共存
+        let mut cursor = m.cursor_back();
+        let six = cursor.current();
+        let mut iter = (&m).into_iter();
+        assert_eq!(iter.next(), Some(&1));
+        cursor.move_next();
+        assert_eq!(cursor.current(), None);
+        assert_eq!(cursor.index(), None);
+        cursor.move_prev();
+        cursor.move_prev();
+        assert_eq!(cursor.current(), Some(&5));
+        assert_eq!(cursor.index(), Some(4));
+        assert_eq!(six, Some(&6));
+
+        let empty: LinkedList<u32> = LinkedList::new();
+        let mut cursor = empty.cursor_front();
+        assert_eq!(cursor.current(), None);
+        assert_eq!(cursor.index(), None);
+        cursor.move_next();
+        cursor.move_prev();
+        assert_eq!(cursor.current(), None);
+        assert_eq!(cursor.peek_next(), None);
+        assert_eq!(cursor.peek_prev(), None);
+        assert_eq!(empty.cursor_back().index(), None);
+    }
+
+    #[test]
+    fn test_cursor_clone_iter() {
+        let m: LinkedList<u32> = (1..=6).collect();
+        let mut cursor = m.cursor_front();
+        cursor.move_next();
+        cursor.move_next();
+        let forked = cursor.clone();
+        cursor.move_next();
+        assert_eq!(forked.current(), Some(&3));
+        assert_eq!(cursor.current(), Some(&4));
+
+        let forward = forked.iter_forward();
+        assert_eq!(forward.len(), 4);
+        assert_eq!(forward.cloned().collect::<Vec<_>>(), &[3, 4, 5, 6]);
+        let backward = forked.iter_backward();
+        assert_eq!(backward.len(), 3);
+        assert_eq!(backward.cloned().collect::<Vec<_>>(), &[3, 2, 1]);
+        assert_eq!(forked.iter_forward().next_back(), Some(&6));
+
+        // ghost上迭代整个链表
+        let mut ghost = m.cursor_back();
+        ghost.move_next();
+        assert_eq!(ghost.iter_forward().cloned().collect::<Vec<_>>(), &[1, 2, 3, 4, 5, 6]);
+        assert_eq!(ghost.iter_backward().cloned().collect::<Vec<_>>(), &[6, 5, 4, 3, 2, 1]);
+
+        let mut m = m;
+        let mut cursor = m.cursor_mut();
+        cursor.move_prev();
+        cursor.move_prev();
+        let view = cursor.as_cursor();
+        assert_eq!(view.current(), Some(&5));
+        assert_eq!(view.index(), Some(4));
+        assert_eq!(view.iter_forward().cloned().collect::<Vec<_>>(), &[5, 6]);
+        *cursor.current().unwrap() = 50;
+        assert_eq!(cursor.as_cursor().peek_prev(), Some(&4));
+        assert_eq!(cursor.as_cursor().current(), Some(&50));
+    }
+
+    fn check_links<T: Eq + std::fmt::Debug, A: NodeAllocator>(list: &LinkedList<T, A>) {
         let from_front: Vec<_> = list.into_iter().collect();
         let from_back: Vec<_> = list.into_iter().rev().collect();
         let re_reved: Vec<_> = from_back.into_iter().rev().collect();
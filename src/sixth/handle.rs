@@ -0,0 +1,451 @@
+// 节点句柄
+// 定时器、取消、LRU这类用法要记住某个元素，之后O(1)删掉或者挪动
+// 直接交出NonNull<Node>的话，元素删掉之后再用就是悬垂指针，拿到别的链表上用也会把两个链表搞乱
+//
+// 所以句柄不直接指节点，而是指链表自己的句柄表里的一格：
+//     NodeHandle { list: 链表的编号, slot: 第几格, generation: 这一格第几次使用 }
+// 用的时候先核对链表编号，再核对这一格的generation，都对上了，格子里的节点就一定还在这个链表上
+// 什么时候作废：
+// 1.元素被删掉：pop、remove、remove_current、retain、clear……最后都走free_node，在那里作废
+// 2.节点整个转到别的链表（append、prepend、split_off(0)这类）：直接扔掉整张表，下次发句柄换个编号
+// 3.一部分节点转走（split_before、split_after、remove_current_as_list）：沿着转走的节点一个个作废
+//   只有发过句柄的链表才需要走，没用句柄的链表这些操作还是O(1)
+//
+// 节点本身不记自己在哪一格，Node的布局和没有句柄时一样（Node<u64>还是3个字）
+// 反过来从节点找格子用一个按节点地址排的BTreeMap，no_std下没有HashMap
+// 所以作废是O(log h)，h是这个链表上还有效的句柄数；没发过句柄的链表map是空的，只多一次判空
+//
+// generation是u32，同一格重复使用2^32次后旧句柄会重新对上，拿到的是这个链表里别的元素，但不会是悬垂指针
+
+use super::{ CursorMut, LinkedList, Link, Node, NodeAllocator };
+use alloc::{ collections::BTreeMap, vec::Vec };
+use core::{ fmt, ptr::NonNull, sync::atomic::{ AtomicUsize, Ordering } };
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct NodeHandle {
+    list: usize,
+    slot: u32,
+    generation: u32,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum HandleError {
+    // 不是这个链表发的，或者发出之后链表的节点整个转走过
+    WrongList,
+    // 元素已经被删掉，或者被分到别的链表去了
+    Stale,
+}
+
+impl fmt::Display for HandleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HandleError::WrongList => f.write_str("handle belongs to a different list"),
+            HandleError::Stale => f.write_str("handle refers to an element no longer in the list"),
+        }
+    }
+}
+
+impl core::error::Error for HandleError {}
+
+// 句柄表，空闲的格子放在free里复用
+pub(super) struct Handles<T> {
+    // 0表示还没发过句柄，还没分配编号
+    id: usize,
+    slots: Vec<Slot<T>>,
+    free: Vec<u32>,
+    // 节点 -> 格子，只有正占着格子的节点在里面
+    by_node: BTreeMap<NonNull<Node<T>>, u32>,
+}
+
+struct Slot<T> {
+    node: Link<T>,
+    generation: u32,
+}
+
+fn next_list_id() -> usize {
+    static NEXT: AtomicUsize = AtomicUsize::new(1);
+    #[cfg(target_has_atomic = "ptr")]
+    {
+        NEXT.fetch_add(1, Ordering::Relaxed)
+    }
+    // 没有CAS的目标（比如thumbv6m）基本都是单核，读了再写就行
+    #[cfg(not(target_has_atomic = "ptr"))]
+    {
+        let id = NEXT.load(Ordering::Relaxed);
+        NEXT.store(id + 1, Ordering::Relaxed);
+        id
+    }
+}
+
+impl<T> Handles<T> {
+    pub(super) const fn new() -> Self {
+        Handles {
+            id: 0,
+            slots: Vec::new(),
+            free: Vec::new(),
+            by_node: BTreeMap::new(),
+        }
+    }
+
+    // 同一个节点发多次句柄得到的是同一个
+    // node要在这个链表上
+    fn issue(&mut self, node: NonNull<Node<T>>) -> NodeHandle {
+        if self.id == 0 {
+            self.id = next_list_id();
+        }
+        let slot = match self.by_node.get(&node) {
+            Some(&slot) => slot,
+            None => {
+                let slot = match self.free.pop() {
+                    Some(slot) => slot,
+                    None => {
+                        assert!(self.slots.len() < u32::MAX as usize, "Too many node handles");
+                        self.slots.push(Slot { node: None, generation: 0 });
+                        (self.slots.len() - 1) as u32
+                    }
+                };
+                self.slots[slot as usize].node = Some(node);
+                self.by_node.insert(node, slot);
+                slot
+            }
+        };
+        NodeHandle {
+            list: self.id,
+            slot,
+            generation: self.slots[slot as usize].generation,
+        }
+    }
+
+    fn lookup(&self, handle: NodeHandle) -> Result<NonNull<Node<T>>, HandleError> {
+        if self.id == 0 || handle.list != self.id {
+            return Err(HandleError::WrongList);
+        }
+        match self.slots.get(handle.slot as usize) {
+            Some(&Slot { node: Some(node), generation }) if generation == handle.generation => {
+                Ok(node)
+            }
+            _ => Err(HandleError::Stale),
+        }
+    }
+
+    // 节点要离开这个链表了，指着它的句柄作废
+    pub(super) fn release(&mut self, node: NonNull<Node<T>>) {
+        if let Some(slot) = self.by_node.remove(&node) {
+            let entry = &mut self.slots[slot as usize];
+            entry.node = None;
+            entry.generation = entry.generation.wrapping_add(1);
+            self.free.push(slot);
+        }
+    }
+
+    // 从front开始的len个节点要离开这个链表了
+    // SAFETY: 沿back走len步都是活着的节点
+    pub(super) unsafe fn release_chain(&mut self, mut front: Link<T>, len: usize) {
+        if self.by_node.is_empty() {
+            return;
+        }
+        for _ in 0..len {
+            let node = front.unwrap();
+            self.release(node);
+            front = (*node.as_ptr()).back;
+        }
+    }
+
+    // 节点全部离开了，整张表扔掉，旧句柄的编号再也对不上
+    pub(super) fn reset(&mut self) {
+        if self.id != 0 {
+            self.id = 0;
+            self.slots.clear();
+            self.free.clear();
+            self.by_node.clear();
+        }
+    }
+}
+
+impl<T, A: NodeAllocator> LinkedList<T, A> {
+    pub fn push_front_handle(&mut self, elem: T) -> NodeHandle {
+        self.push_front(elem);
+        self.handles.issue(self.front.unwrap())
+    }
+
+    pub fn push_back_handle(&mut self, elem: T) -> NodeHandle {
+        self.push_back(elem);
+        self.handles.issue(self.back.unwrap())
+    }
+
+    pub fn get(&self, handle: NodeHandle) -> Result<&T, HandleError> {
+        let node = self.handles.lookup(handle)?;
+        unsafe { Ok(&(*node.as_ptr()).elem) }
+    }
+
+    pub fn get_mut(&mut self, handle: NodeHandle) -> Result<&mut T, HandleError> {
+        let node = self.handles.lookup(handle)?;
+        unsafe { Ok(&mut (*node.as_ptr()).elem) }
+    }
+
+    // 删掉之后这个句柄就作废了
+    pub fn remove(&mut self, handle: NodeHandle) -> Result<T, HandleError> {
+        let node = self.handles.lookup(handle)?;
+        unsafe { Ok(self.remove_node(node)) }
+    }
+
+    // 挪动不改变句柄
+    pub fn move_to_front(&mut self, handle: NodeHandle) -> Result<(), HandleError> {
+        let node = self.handles.lookup(handle)?;
+        unsafe {
+            self.unlink_node(node);
+            self.link_front_node(node);
+        }
+        Ok(())
+    }
+
+    pub fn move_to_back(&mut self, handle: NodeHandle) -> Result<(), HandleError> {
+        let node = self.handles.lookup(handle)?;
+        unsafe {
+            self.unlink_node(node);
+            (*node.as_ptr()).front = self.back;
+            match self.back {
+                Some(old) => (*old.as_ptr()).back = Some(node),
+                None => self.front = Some(node),
+            }
+            self.back = Some(node);
+            self.len += 1;
+        }
+        Ok(())
+    }
+
+    // 光标指向句柄的元素
+    // 节点是O(1)找到的，但光标要带index，得从节点往front数，O(index)
+    pub fn cursor_at(&mut self, handle: NodeHandle) -> Result<CursorMut<'_, T, A>, HandleError> {
+        let node = self.handles.lookup(handle)?;
+        let mut index = 0;
+        let mut cur = node;
+        unsafe {
+            while let Some(prev) = (*cur.as_ptr()).front {
+                cur = prev;
+                index += 1;
+            }
+        }
+        Ok(CursorMut {
+            list: self,
+            cur: Some(node),
+            index: Some(index),
+        })
+    }
+}
+
+impl<'a, T, A: NodeAllocator> CursorMut<'a, T, A> {
+    // 当前元素的句柄，ghost上没有
+    pub fn handle(&mut self) -> Option<NodeHandle> {
+        let cur = self.cur?;
+        Some(self.list.handles.issue(cur))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::HandleError;
+    use crate::sixth::LinkedList;
+    use crate::test_util::to_vec;
+
+    #[test]
+    fn basics() {
+        let mut list = LinkedList::new();
+        let a = list.push_back_handle('a');
+        let b = list.push_back_handle('b');
+        let c = list.push_front_handle('c');
+        assert_eq!(list.get(a), Ok(&'a'));
+        assert_eq!(list.get(b), Ok(&'b'));
+        *list.get_mut(c).unwrap() = 'C';
+        assert_eq!(to_vec(&list), ['C', 'a', 'b']);
+
+        list.move_to_front(b).unwrap();
+        assert_eq!(to_vec(&list), ['b', 'C', 'a']);
+        list.move_to_back(b).unwrap();
+        list.move_to_back(c).unwrap();
+        assert_eq!(to_vec(&list), ['a', 'b', 'C']);
+        // 挪动之后句柄不变
+        assert_eq!(list.get(b), Ok(&'b'));
+
+        assert_eq!(list.remove(b), Ok('b'));
+        assert_eq!(list.len(), 2);
+        assert_eq!(to_vec(&list), ['a', 'C']);
+        assert_eq!(list.back(), Some(&'C'));
+
+        // 单元素时挪动
+        assert_eq!(list.remove(a), Ok('a'));
+        list.move_to_front(c).unwrap();
+        list.move_to_back(c).unwrap();
+        assert_eq!(to_vec(&list), ['C']);
+        assert_eq!(list.remove(c), Ok('C'));
+        assert!(list.is_empty());
+        assert_eq!(list.front(), None);
+        assert_eq!(list.back(), None);
+    }
+
+    #[test]
+    fn stale_after_removal() {
+        let mut list: LinkedList<i32> = (0..3).collect();
+        let h = list.push_back_handle(3);
+        assert_eq!(list.remove(h), Ok(3));
+        assert_eq!(list.remove(h), Err(HandleError::Stale));
+        assert_eq!(list.get(h), Err(HandleError::Stale));
+        assert_eq!(list.move_to_front(h), Err(HandleError::Stale));
+        assert!(list.cursor_at(h).is_err());
+
+        // 格子复用之后旧句柄还是对不上
+        let h2 = list.push_back_handle(4);
+        assert_ne!(h, h2);
+        assert_eq!(list.get(h), Err(HandleError::Stale));
+        assert_eq!(list.get(h2), Ok(&4));
+
+        // 其它删除方式一样作废
+        let h3 = list.push_front_handle(-1);
+        assert_eq!(list.pop_front(), Some(-1));
+        assert_eq!(list.get(h3), Err(HandleError::Stale));
+        list.retain(|&x| x != 4);
+        assert_eq!(list.get(h2), Err(HandleError::Stale));
+
+        let mut cursor = list.cursor_front_mut();
+        let h4 = cursor.handle().unwrap();
+        cursor.remove_current();
+        assert_eq!(list.get(h4), Err(HandleError::Stale));
+        list.clear();
+        assert_eq!(to_vec(&list), []);
+    }
+
+    #[test]
+    fn wrong_list() {
+        let mut a = LinkedList::new();
+        let mut b = LinkedList::new();
+        let ha = a.push_back_handle(1);
+        assert_eq!(b.get(ha), Err(HandleError::WrongList));
+        b.push_back(1);
+        assert_eq!(b.remove(ha), Err(HandleError::WrongList));
+        let hb = b.push_back_handle(2);
+        assert_eq!(a.get(hb), Err(HandleError::WrongList));
+        assert_eq!(a.get(ha), Ok(&1));
+    }
+
+    #[test]
+    fn transfers() {
+        // 整个转走：旧句柄都对不上
+        let mut a = LinkedList::new();
+        let h = a.push_back_handle(1);
+        let mut b = LinkedList::new();
+        b.append(&mut a);
+        assert_eq!(a.get(h), Err(HandleError::WrongList));
+        assert_eq!(b.get(h), Err(HandleError::WrongList));
+        // 转过去的节点可以在新链表上重新发句柄
+        let h = b.cursor_front_mut().handle().unwrap();
+        assert_eq!(b.get(h), Ok(&1));
+
+        let mut c = LinkedList::new();
+        c.push_back(0);
+        c.prepend(&mut b);
+        assert_eq!(b.get(h), Err(HandleError::WrongList));
+        assert_eq!(to_vec(&c), [1, 0]);
+
+        // 分走一部分：分走的作废，留下的照常
+        let mut list = LinkedList::new();
+        let handles: Vec<_> = (0..6).map(|i| list.push_back_handle(i)).collect();
+        let tail = list.split_off(4);
+        assert_eq!(to_vec(&tail), [4, 5]);
+        assert_eq!(list.get(handles[4]), Err(HandleError::Stale));
+        assert_eq!(list.get(handles[3]), Ok(&3));
+
+        let mut cursor = list.cursor_at(handles[2]).unwrap();
+        assert_eq!(cursor.index(), Some(2));
+        let front = cursor.split_before();
+        assert_eq!(to_vec(&front), [0, 1]);
+        assert_eq!(list.get(handles[0]), Err(HandleError::Stale));
+        assert_eq!(list.get(handles[2]), Ok(&2));
+
+        let mut cursor = list.cursor_at(handles[2]).unwrap();
+        let single = cursor.remove_current_as_list().unwrap();
+        assert_eq!(to_vec(&single), [2]);
+        assert_eq!(list.get(handles[2]), Err(HandleError::Stale));
+        assert_eq!(list.get(handles[3]), Ok(&3));
+
+        // 别的链表拼进来的节点不带句柄
+        let mut cursor = list.cursor_at(handles[3]).unwrap();
+        cursor.splice_before(front);
+        assert_eq!(list.get(handles[0]), Err(HandleError::Stale));
+        assert_eq!(list.get(handles[3]), Ok(&3));
+        assert_eq!(to_vec(&list), [0, 1, 3]);
+        let all = list.split_off(0);
+        assert_eq!(list.get(handles[3]), Err(HandleError::WrongList));
+        assert_eq!(to_vec(&all), [0, 1, 3]);
+    }
+
+    #[test]
+    fn cursor_handle() {
+        let mut list: LinkedList<i32> = (0..5).collect();
+        let mut cursor = list.cursor_at_mut(3);
+        let h = cursor.handle().unwrap();
+        // 同一个节点的句柄是同一个
+        assert_eq!(cursor.handle(), Some(h));
+        cursor.move_next();
+        cursor.move_next();
+        assert_eq!(cursor.handle(), None);
+
+        list.push_front(-1);
+        let mut cursor = list.cursor_at(h).unwrap();
+        assert_eq!(cursor.index(), Some(4));
+        assert_eq!(cursor.current(), Some(&mut 3));
+        cursor.insert_before(10);
+        assert_eq!(to_vec(&list), [-1, 0, 1, 2, 10, 3, 4]);
+
+        // 排序只是重新连节点，句柄跟着元素走
+        list.sort();
+        assert_eq!(list.get(h), Ok(&3));
+        assert_eq!(list.cursor_at(h).unwrap().index(), Some(4));
+    }
+
+    // 定时器：按到期时间排好，取消的时候O(1)删掉
+    #[test]
+    fn timers() {
+        let mut timers = LinkedList::new();
+        let handles: Vec<_> = (0..10).map(|t| timers.push_back_handle(t * 10)).collect();
+        for h in handles.iter().skip(1).step_by(2) {
+            timers.remove(*h).unwrap();
+        }
+        assert_eq!(to_vec(&timers), [0, 20, 40, 60, 80]);
+        for h in handles.iter().skip(1).step_by(2) {
+            assert_eq!(timers.remove(*h), Err(HandleError::Stale));
+        }
+        timers.move_to_back(handles[0]).unwrap();
+        assert_eq!(timers.pop_back(), Some(0));
+        assert!(timers.get(handles[0]).is_err());
+    }
+
+    #[test]
+    fn node_cache() {
+        // 节点进了缓存又被复用，旧句柄不能指到新元素上
+        let mut list = LinkedList::with_node_cache(4);
+        let h = list.push_back_handle(1);
+        list.pop_back();
+        list.push_back(2);
+        assert_eq!(list.get(h), Err(HandleError::Stale));
+        let h2 = list.cursor_front_mut().handle().unwrap();
+        assert_eq!(list.get(h2), Ok(&2));
+    }
+
+    // 句柄表挂在链表上，节点里不多存东西
+    #[test]
+    fn node_layout() {
+        use core::mem::size_of;
+        use crate::sixth::Node;
+
+        assert_eq!(size_of::<Node<u64>>(), 2 * size_of::<usize>() + size_of::<u64>());
+    }
+
+    #[test]
+    fn error_display() {
+        extern crate std;
+        use std::string::ToString;
+
+        assert_eq!(HandleError::Stale.to_string(), "handle refers to an element no longer in the list");
+        assert_eq!(HandleError::WrongList.to_string(), "handle belongs to a different list");
+    }
+}
@@ -0,0 +1,941 @@
+// 跳表
+// 有序的链表，查找、按序号取都是O(log n)（期望）
+//
+// 在sixth那种链表上再加几层“快车道”：每个节点随机决定自己有几层，
+// 第i层只串起层数大于i的节点，越往上越稀疏。查找从最高层开始，走不动了就下一层
+//
+//     层2  head ----------------------> 30 ----------------> None
+//     层1  head ------> 10 -----------> 30 ------> 50 -----> None
+//     层0  head -> 5 -> 10 -> 20 -> 25 -> 30 -> 40 -> 50 -> None
+//
+// 每条链接还记着跨过了多少个第0层的节点（span），沿路加起来就是排名，所以按序号取也是O(log n)
+// 第0层另外有一条往回的prev链，用来从后往前迭代
+//
+// 层数用自己的伪随机数生成器决定，种子固定，同样的操作序列得到同样的结构，测试可以复现
+
+use alloc::{ boxed::Box, vec::Vec };
+use core::{
+    borrow::Borrow,
+    cmp::Ordering,
+    fmt::{ self, Debug },
+    hash::{ Hash, Hasher },
+    marker::PhantomData,
+    ops::{ Bound, RangeBounds },
+    ptr::NonNull,
+};
+
+// 每层晋升的概率是1/2，32层足够2^32个元素用
+const MAX_LEVEL: usize = 32;
+
+const DEFAULT_SEED: u64 = 0x2545_f491_4f6c_dd1d;
+
+// 和sixth一样用NonNull + PhantomData，对T协变
+pub struct SkipList<T> {
+    // 头节点只有各层的链接，没有元素；层数就是整个表当前的最高层数
+    head: Vec<Level<T>>,
+    back: Link<T>,
+    len: usize,
+    rng: Rng,
+    _boo: PhantomData<T>,
+}
+
+type Link<T> = Option<NonNull<Node<T>>>;
+
+struct Node<T> {
+    elem: T,
+    prev: Link<T>,
+    levels: Box<[Level<T>]>,
+}
+
+// next为None时，span是到表尾后面那个假想位置（排名len + 1）的距离
+struct Level<T> {
+    next: Link<T>,
+    span: usize,
+}
+
+// 元素的顺序就是链表的结构，改了元素可能就乱序了，所以只有不可变迭代器
+pub struct Iter<'a, T> {
+    front: Link<T>,
+    back: Link<T>,
+    len: usize,
+    _boo: PhantomData<&'a T>,
+}
+
+pub struct IntoIter<T> {
+    list: SkipList<T>,
+}
+
+// xorshift64*，不依赖外部crate，也不需要std
+#[derive(Clone, Debug)]
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: u64) -> Self {
+        // 状态不能是0，否则一直是0
+        Rng(if seed == 0 { DEFAULT_SEED } else { seed })
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let mut x = self.0;
+        x ^= x >> 12;
+        x ^= x << 25;
+        x ^= x >> 27;
+        self.0 = x;
+        x.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+}
+
+impl<T> Clone for Level<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Level<T> {}
+
+impl<T> SkipList<T> {
+    pub fn new() -> Self {
+        Self::with_seed(DEFAULT_SEED)
+    }
+
+    // 同一个种子、同样的操作，得到一模一样的层数分布
+    pub fn with_seed(seed: u64) -> Self {
+        SkipList {
+            head: Vec::new(),
+            back: None,
+            len: 0,
+            rng: Rng::new(seed),
+            _boo: PhantomData,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn front(&self) -> Option<&T> {
+        unsafe { self.head.first().and_then(|level| level.next).map(|node| &(*node.as_ptr()).elem) }
+    }
+
+    pub fn back(&self) -> Option<&T> {
+        unsafe { self.back.map(|node| &(*node.as_ptr()).elem) }
+    }
+
+    pub fn pop_front(&mut self) -> Option<T> {
+        let (update, _) = self.path(|_| false);
+        let first = self.head.first()?.next?;
+        unsafe { Some(self.unlink(&update, first)) }
+    }
+
+    pub fn pop_back(&mut self) -> Option<T> {
+        let last = self.back?;
+        // 每一层都走到last前面为止
+        let (update, _) = self.path(|elem| !core::ptr::eq(elem, unsafe { &(*last.as_ptr()).elem }));
+        unsafe { Some(self.unlink(&update, last)) }
+    }
+
+    pub fn clear(&mut self) {
+        while self.pop_front().is_some() {}
+    }
+
+    // 按序号取，O(log n)
+    pub fn get_index(&self, index: usize) -> Option<&T> {
+        if index >= self.len {
+            return None;
+        }
+        // 排名从1开始，头节点是0
+        let target = index + 1;
+        let mut x: Link<T> = None;
+        let mut rank = 0;
+        for i in (0..self.head.len()).rev() {
+            loop {
+                let level = unsafe { self.level(x, i) };
+                match level.next {
+                    Some(next) if rank + level.span <= target => {
+                        rank += level.span;
+                        x = Some(next);
+                    }
+                    _ => {
+                        break;
+                    }
+                }
+            }
+            if rank == target {
+                break;
+            }
+        }
+        unsafe { x.map(|node| &(*node.as_ptr()).elem) }
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            front: self.head.first().and_then(|level| level.next),
+            back: self.back,
+            len: self.len,
+            _boo: PhantomData,
+        }
+    }
+
+    // 抛硬币，连续正面几次就多几层
+    fn random_level(&mut self) -> usize {
+        1 + (self.rng.next_u64().trailing_ones() as usize).min(MAX_LEVEL - 1)
+    }
+
+    // SAFETY: at是这个表里的节点（None是头节点），i小于它的层数
+    unsafe fn level(&self, at: Link<T>, i: usize) -> &Level<T> {
+        match at {
+            Some(node) => &(&(*node.as_ptr()).levels)[i],
+            None => &self.head[i],
+        }
+    }
+
+    // 第0层上at的下一个，表空的时候头节点一层都没有
+    unsafe fn next(&self, at: Link<T>) -> Link<T> {
+        match at {
+            Some(node) => (&(*node.as_ptr()).levels)[0].next,
+            None => self.head.first().and_then(|level| level.next),
+        }
+    }
+
+    unsafe fn level_mut(&mut self, at: Link<T>, i: usize) -> &mut Level<T> {
+        match at {
+            Some(node) => &mut (&mut (*node.as_ptr()).levels)[i],
+            None => &mut self.head[i],
+        }
+    }
+
+    // 从最高层往下走，每层一直走到下一个元素不再满足before为止
+    // 返回每层停下的位置（None是头节点）和它的排名（头节点是0）
+    // before必须对有序的元素先true后false
+    fn path<F: FnMut(&T) -> bool>(
+        &self,
+        mut before: F
+    ) -> ([Link<T>; MAX_LEVEL], [usize; MAX_LEVEL]) {
+        let mut update = [None; MAX_LEVEL];
+        let mut ranks = [0; MAX_LEVEL];
+        let mut x: Link<T> = None;
+        let mut rank = 0;
+        for i in (0..self.head.len()).rev() {
+            loop {
+                let level = unsafe { self.level(x, i) };
+                match level.next {
+                    Some(next) if before(unsafe { &(*next.as_ptr()).elem }) => {
+                        rank += level.span;
+                        x = Some(next);
+                    }
+                    _ => {
+                        break;
+                    }
+                }
+            }
+            update[i] = x;
+            ranks[i] = rank;
+        }
+        (update, ranks)
+    }
+
+    // 把x摘下来，update是path走到x前面时记下的位置
+    unsafe fn unlink(&mut self, update: &[Link<T>; MAX_LEVEL], x: NonNull<Node<T>>) -> T {
+        let x_levels: &[Level<T>] = &(*x.as_ptr()).levels;
+        for (i, &at) in update.iter().enumerate().take(self.head.len()) {
+            let x_level = x_levels.get(i).copied();
+            let pred = self.level_mut(at, i);
+            match x_level {
+                Some(x_level) if pred.next == Some(x) => {
+                    pred.next = x_level.next;
+                    pred.span += x_level.span - 1;
+                }
+                _ => {
+                    pred.span -= 1;
+                }
+            }
+        }
+
+        let node = Box::from_raw(x.as_ptr());
+        match node.levels[0].next {
+            Some(next) => {
+                (*next.as_ptr()).prev = node.prev;
+            }
+            None => {
+                self.back = node.prev;
+            }
+        }
+        // 最高的几层可能空了
+        while self.head.last().is_some_and(|level| level.next.is_none()) {
+            self.head.pop();
+        }
+        self.len -= 1;
+        node.elem
+    }
+}
+
+impl<T: Ord> SkipList<T> {
+    // 相等的元素插在已有的后面
+    pub fn insert(&mut self, elem: T) {
+        let (update, ranks) = self.path(|e| e <= &elem);
+        let height = self.random_level();
+        while self.head.len() < height {
+            // 新的一层上头节点直接指向表尾，跨过所有元素
+            self.head.push(Level {
+                next: None,
+                span: self.len + 1,
+            });
+        }
+
+        let new_rank = ranks[0] + 1;
+        let new = NonNull::from(
+            Box::leak(
+                Box::new(Node {
+                    elem,
+                    prev: update[0],
+                    levels: (0..height)
+                        .map(|_| Level { next: None, span: 0 })
+                        .collect(),
+                })
+            )
+        );
+
+        unsafe {
+            for i in 0..self.head.len() {
+                let pred = self.level_mut(update[i], i);
+                if i < height {
+                    // pred原来跨到排名ranks[i] + span的位置，插入后那个位置往后挪了一位
+                    let level = Level {
+                        next: pred.next,
+                        span: ranks[i] + pred.span + 1 - new_rank,
+                    };
+                    pred.next = Some(new);
+                    pred.span = new_rank - ranks[i];
+                    (&mut (*new.as_ptr()).levels)[i] = level;
+                } else {
+                    // 更高的层跨过了新节点
+                    pred.span += 1;
+                }
+            }
+
+            match (&(*new.as_ptr()).levels)[0].next {
+                Some(next) => {
+                    (*next.as_ptr()).prev = Some(new);
+                }
+                None => {
+                    self.back = Some(new);
+                }
+            }
+        }
+        self.len += 1;
+    }
+
+    // 有多个相等的元素时移除第一个
+    pub fn remove<Q: Ord + ?Sized>(&mut self, key: &Q) -> Option<T> where T: Borrow<Q> {
+        let (update, _) = self.path(|e| e.borrow() < key);
+        let found = unsafe { self.next(update[0])? };
+        unsafe {
+            if (*found.as_ptr()).elem.borrow() == key {
+                Some(self.unlink(&update, found))
+            } else {
+                None
+            }
+        }
+    }
+
+    pub fn get<Q: Ord + ?Sized>(&self, key: &Q) -> Option<&T> where T: Borrow<Q> {
+        self.lower_bound(key).filter(|(_, elem)| (*elem).borrow() == key).map(|(_, elem)| elem)
+    }
+
+    pub fn contains<Q: Ord + ?Sized>(&self, key: &Q) -> bool where T: Borrow<Q> {
+        self.get(key).is_some()
+    }
+
+    // 第一个等于key的元素的序号
+    pub fn index_of<Q: Ord + ?Sized>(&self, key: &Q) -> Option<usize> where T: Borrow<Q> {
+        self.lower_bound(key)
+            .filter(|(_, elem)| (*elem).borrow() == key)
+            .map(|(index, _)| index)
+    }
+
+    // 范围内的元素，两头都能迭代，长度也是准的
+    pub fn range<Q, R>(&self, range: R) -> Iter<'_, T>
+        where Q: Ord + ?Sized, T: Borrow<Q>, R: RangeBounds<Q>
+    {
+        // 第一个不在起点前面的
+        let (start, start_ranks) = self.path(|e| {
+            match range.start_bound() {
+                Bound::Included(s) => e.borrow() < s,
+                Bound::Excluded(s) => e.borrow() <= s,
+                Bound::Unbounded => false,
+            }
+        });
+        // 最后一个不在终点后面的
+        let (end, end_ranks) = self.path(|e| {
+            match range.end_bound() {
+                Bound::Included(s) => e.borrow() <= s,
+                Bound::Excluded(s) => e.borrow() < s,
+                Bound::Unbounded => true,
+            }
+        });
+
+        // 序号在[start_ranks[0], end_ranks[0])之间
+        let len = end_ranks[0].saturating_sub(start_ranks[0]);
+        if len == 0 {
+            return Iter {
+                front: None,
+                back: None,
+                len: 0,
+                _boo: PhantomData,
+            };
+        }
+        Iter {
+            front: unsafe { self.next(start[0]) },
+            back: end[0],
+            len,
+            _boo: PhantomData,
+        }
+    }
+
+    // 第一个不小于key的元素和它的序号
+    fn lower_bound<Q: Ord + ?Sized>(&self, key: &Q) -> Option<(usize, &T)> where T: Borrow<Q> {
+        let (update, ranks) = self.path(|e| e.borrow() < key);
+        unsafe { self.next(update[0]).map(|node| (ranks[0], &(*node.as_ptr()).elem)) }
+    }
+}
+
+impl<T> Drop for SkipList<T> {
+    fn drop(&mut self) {
+        // 第0层串着所有节点，沿着它释放就行
+        let mut cur = self.head.first().and_then(|level| level.next);
+        while let Some(node) = cur {
+            unsafe {
+                let node = Box::from_raw(node.as_ptr());
+                cur = node.levels[0].next;
+            }
+        }
+    }
+}
+
+impl<T> IntoIterator for SkipList<T> {
+    type IntoIter = IntoIter<T>;
+    type Item = T;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter { list: self }
+    }
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.list.pop_front()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.list.len, Some(self.list.len))
+    }
+}
+
+impl<T> DoubleEndedIterator for IntoIter<T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.list.pop_back()
+    }
+}
+
+impl<T> ExactSizeIterator for IntoIter<T> {
+    fn len(&self) -> usize {
+        self.list.len
+    }
+}
+
+impl<'a, T> IntoIterator for &'a SkipList<T> {
+    type IntoIter = Iter<'a, T>;
+    type Item = &'a T;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.len > 0 {
+            self.front.map(|node| unsafe {
+                self.len -= 1;
+                self.front = (&(*node.as_ptr()).levels)[0].next;
+                &(*node.as_ptr()).elem
+            })
+        } else {
+            None
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for Iter<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.len > 0 {
+            self.back.map(|node| unsafe {
+                self.len -= 1;
+                self.back = (*node.as_ptr()).prev;
+                &(*node.as_ptr()).elem
+            })
+        } else {
+            None
+        }
+    }
+}
+
+impl<'a, T> ExactSizeIterator for Iter<'a, T> {
+    fn len(&self) -> usize {
+        self.len
+    }
+}
+
+impl<'a, T> Clone for Iter<'a, T> {
+    fn clone(&self) -> Self {
+        Iter { ..*self }
+    }
+}
+
+// ================================================
+impl<T> Default for SkipList<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// 一个个节点照原样复制，层数、span都不变，随机数状态原样带上
+// 所以复制出来的表之后的行为和原表一样
+// 沿第0层走，每层记着复制出来的最后一个节点，新节点有几层就接到哪几层后面
+impl<T: Clone> Clone for SkipList<T> {
+    fn clone(&self) -> Self {
+        let mut new_list = SkipList {
+            head: self.head
+                .iter()
+                .map(|level| Level { next: None, span: level.span })
+                .collect(),
+            back: None,
+            len: 0,
+            rng: self.rng.clone(),
+            _boo: PhantomData,
+        };
+        let mut tails: [Link<T>; MAX_LEVEL] = [None; MAX_LEVEL];
+        let mut cur = self.head.first().and_then(|level| level.next);
+        while let Some(node) = cur {
+            unsafe {
+                let old = &*node.as_ptr();
+                // clone panic的话new_list照常drop，已经接上第0层的节点都能释放
+                let new = NonNull::from(
+                    Box::leak(
+                        Box::new(Node {
+                            elem: old.elem.clone(),
+                            prev: new_list.back,
+                            levels: old.levels
+                                .iter()
+                                .map(|level| Level { next: None, span: level.span })
+                                .collect(),
+                        })
+                    )
+                );
+                for (i, tail) in tails.iter_mut().enumerate().take(old.levels.len()) {
+                    new_list.level_mut(*tail, i).next = Some(new);
+                    *tail = Some(new);
+                }
+                new_list.back = Some(new);
+                new_list.len += 1;
+                cur = old.levels[0].next;
+            }
+        }
+        new_list
+    }
+}
+
+// 不是接到后面，而是一个个插到该在的位置
+impl<T: Ord> Extend<T> for SkipList<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for item in iter {
+            self.insert(item);
+        }
+    }
+}
+
+impl<T: Ord> FromIterator<T> for SkipList<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut list = Self::new();
+        list.extend(iter);
+        list
+    }
+}
+
+impl<T: Debug> Debug for SkipList<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self).finish()
+    }
+}
+
+// 只比较元素，层数结构不算
+impl<T: PartialEq> PartialEq for SkipList<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.into_iter().eq(other)
+    }
+}
+
+impl<T: Eq> Eq for SkipList<T> {}
+
+impl<T: PartialOrd> PartialOrd for SkipList<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.into_iter().partial_cmp(other)
+    }
+}
+
+impl<T: Ord> Ord for SkipList<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.into_iter().cmp(other)
+    }
+}
+
+impl<T: Hash> Hash for SkipList<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.len().hash(state);
+        for item in self {
+            item.hash(state);
+        }
+    }
+}
+
+unsafe impl<T: Send> Send for SkipList<T> {}
+unsafe impl<T: Sync> Sync for SkipList<T> {}
+
+unsafe impl<'a, T: Sync> Send for Iter<'a, T> {}
+unsafe impl<'a, T: Sync> Sync for Iter<'a, T> {}
+
+#[cfg(test)]
+mod test {
+    use super::{ Rng, SkipList };
+
+    // 检查每一层的链接、span、prev、长度和顺序
+    fn check<T: Ord + core::fmt::Debug>(list: &SkipList<T>) {
+        // 第0层上每个节点的排名
+        let mut ranks = std::collections::HashMap::new();
+        let mut cur = list.head.first().and_then(|level| level.next);
+        let mut prev = None;
+        let mut rank = 0;
+        unsafe {
+            while let Some(node) = cur {
+                rank += 1;
+                ranks.insert(node, rank);
+                assert_eq!((*node.as_ptr()).prev, prev);
+                if let Some(prev) = prev {
+                    assert!((*prev.as_ptr()).elem <= (*node.as_ptr()).elem);
+                }
+                prev = Some(node);
+                cur = (*node.as_ptr()).levels[0].next;
+            }
+        }
+        assert_eq!(rank, list.len());
+        assert_eq!(list.back, prev);
+
+        if let Some(top) = list.head.last() {
+            assert!(top.next.is_some(), "empty top level");
+        }
+        for i in 0..list.head.len() {
+            let mut at = None;
+            let mut at_rank = 0;
+            loop {
+                let level = unsafe { list.level(at, i) };
+                let next_rank = match level.next {
+                    Some(next) => ranks[&next],
+                    None => list.len() + 1,
+                };
+                assert_eq!(level.span, next_rank - at_rank, "level {i} rank {at_rank}");
+                match level.next {
+                    Some(next) => {
+                        at = Some(next);
+                        at_rank = next_rank;
+                    }
+                    None => {
+                        break;
+                    }
+                }
+            }
+        }
+        assert_eq!(list.iter().len(), list.len());
+        assert!(list.iter().rev().eq(list.iter().collect::<Vec<_>>().into_iter().rev()));
+    }
+
+    // 按顺序列出每个节点的层数
+    fn heights<T>(list: &SkipList<T>) -> Vec<usize> {
+        let mut heights = Vec::new();
+        let mut cur = list.head.first().and_then(|level| level.next);
+        while let Some(node) = cur {
+            unsafe {
+                let levels: &[_] = &(*node.as_ptr()).levels;
+                heights.push(levels.len());
+                cur = levels[0].next;
+            }
+        }
+        heights
+    }
+
+    #[test]
+    fn basics() {
+        let mut list = SkipList::new();
+        assert_eq!(list.pop_front(), None);
+        assert_eq!(list.pop_back(), None);
+        assert_eq!(list.front(), None);
+        check(&list);
+
+        for x in [5, 1, 4, 2, 3] {
+            list.insert(x);
+            check(&list);
+        }
+        assert_eq!(list.len(), 5);
+        assert_eq!(list.front(), Some(&1));
+        assert_eq!(list.back(), Some(&5));
+        assert!(list.iter().eq(&[1, 2, 3, 4, 5]));
+
+        assert_eq!(list.remove(&3), Some(3));
+        assert_eq!(list.remove(&3), None);
+        check(&list);
+        assert_eq!(list.pop_front(), Some(1));
+        assert_eq!(list.pop_back(), Some(5));
+        check(&list);
+        assert!(list.iter().eq(&[2, 4]));
+
+        list.clear();
+        assert!(list.is_empty());
+        assert!(list.head.is_empty());
+        check(&list);
+    }
+
+    #[test]
+    fn get() {
+        let list: SkipList<i32> = (0..100).map(|x| x * 2).collect();
+        check(&list);
+        assert_eq!(list.get(&40), Some(&40));
+        assert_eq!(list.get(&41), None);
+        assert!(list.contains(&198));
+        assert!(!list.contains(&-2));
+        assert_eq!(list.index_of(&40), Some(20));
+        assert_eq!(list.index_of(&41), None);
+
+        for i in 0..100 {
+            assert_eq!(list.get_index(i), Some(&((i as i32) * 2)));
+        }
+        assert_eq!(list.get_index(100), None);
+
+        // Borrow：String的表可以用&str查
+        let words: SkipList<String> = ["b", "a", "c"].iter().map(|s| s.to_string()).collect();
+        assert_eq!(words.get("b").map(String::as_str), Some("b"));
+        assert_eq!(words.index_of("c"), Some(2));
+    }
+
+    #[test]
+    fn duplicates() {
+        // 按key排序，相等的按插入顺序排
+        #[derive(Debug)]
+        struct Entry(i32, char);
+        impl PartialEq for Entry {
+            fn eq(&self, other: &Self) -> bool {
+                self.0 == other.0
+            }
+        }
+        impl Eq for Entry {}
+        impl PartialOrd for Entry {
+            fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+        impl Ord for Entry {
+            fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+                self.0.cmp(&other.0)
+            }
+        }
+
+        let mut list = SkipList::new();
+        list.insert(Entry(1, 'a'));
+        list.insert(Entry(2, 'b'));
+        list.insert(Entry(1, 'c'));
+        list.insert(Entry(1, 'd'));
+        check(&list);
+        let tags: String = list.iter().map(|e| e.1).collect();
+        assert_eq!(tags, "acdb");
+
+        assert_eq!(list.index_of(&Entry(1, ' ')), Some(0));
+        assert_eq!(list.remove(&Entry(1, ' ')).map(|e| e.1), Some('a'));
+        assert_eq!(list.get(&Entry(1, ' ')).map(|e| e.1), Some('c'));
+        assert_eq!(list.range(Entry(1, ' ')..=Entry(1, ' ')).len(), 2);
+        check(&list);
+    }
+
+    #[test]
+    fn range() {
+        let list: SkipList<i32> = (0..50).collect();
+
+        let collect = |iter: super::Iter<'_, i32>| iter.copied().collect::<Vec<_>>();
+        assert_eq!(collect(list.range(10..15)), [10, 11, 12, 13, 14]);
+        assert_eq!(collect(list.range(10..=15)), [10, 11, 12, 13, 14, 15]);
+        assert_eq!(collect(list.range(47..)), [47, 48, 49]);
+        assert_eq!(collect(list.range(..3)), [0, 1, 2]);
+        assert_eq!(list.range(..).len(), 50);
+        assert_eq!(list.range(20..20).len(), 0);
+        assert_eq!(list.range(60..70).len(), 0);
+        assert_eq!(list.range(-10..0).len(), 0);
+        assert_eq!(
+            collect(list.range((core::ops::Bound::Excluded(5), core::ops::Bound::Included(8)))),
+            [6, 7, 8]
+        );
+
+        let mut iter = list.range(10..20);
+        assert_eq!(iter.len(), 10);
+        assert_eq!(iter.next(), Some(&10));
+        assert_eq!(iter.next_back(), Some(&19));
+        assert_eq!(iter.len(), 8);
+        assert_eq!(iter.rev().copied().collect::<Vec<_>>(), [18, 17, 16, 15, 14, 13, 12, 11]);
+
+        // 稀疏的值
+        let list: SkipList<i32> = (0..50).map(|x| x * 10).collect();
+        assert_eq!(collect(list.range(15..41)), [20, 30, 40]);
+        assert_eq!(collect(list.range(15..=40)), [20, 30, 40]);
+        assert_eq!(list.range(11..19).len(), 0);
+    }
+
+    #[test]
+    fn iter() {
+        let list: SkipList<i32> = [3, 1, 2].into_iter().collect();
+        let mut iter = list.iter();
+        assert_eq!(iter.size_hint(), (3, Some(3)));
+        assert_eq!(iter.next(), Some(&1));
+        assert_eq!(iter.next_back(), Some(&3));
+        assert_eq!(iter.next(), Some(&2));
+        assert_eq!(iter.next_back(), None);
+
+        let mut iter = list.into_iter();
+        assert_eq!(iter.len(), 3);
+        assert_eq!(iter.next_back(), Some(3));
+        assert_eq!(iter.next(), Some(1));
+        assert_eq!(iter.next(), Some(2));
+        assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    fn seeded() {
+        // 同样的种子、同样的操作，层数结构完全一样
+        let build = |seed| {
+            let mut list = SkipList::with_seed(seed);
+            for x in 0..200 {
+                list.insert(x);
+            }
+            heights(&list)
+        };
+        assert_eq!(build(7), build(7));
+        assert_ne!(build(7), build(8));
+        // 0也是合法的种子
+        assert_eq!(build(0).len(), 200);
+    }
+
+    #[test]
+    fn clone() {
+        let mut list = SkipList::with_seed(3);
+        list.extend((0..100).rev());
+        let mut cloned = list.clone();
+        check(&cloned);
+        assert_eq!(heights(&cloned), heights(&list));
+        assert_eq!(cloned.head.len(), list.head.len());
+
+        // 随机数状态一样，之后同样的插入得到同样的结构
+        for x in [50, -1, 100, 50] {
+            list.insert(x);
+            cloned.insert(x);
+        }
+        check(&cloned);
+        assert_eq!(heights(&cloned), heights(&list));
+        assert!(cloned.iter().eq(list.iter()));
+
+        let empty = SkipList::<i32>::new().clone();
+        check(&empty);
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn random_ops() {
+        // 和排好序的Vec对照
+        let mut rng = Rng::new(42);
+        let mut list = SkipList::with_seed(1);
+        let mut model: Vec<u64> = Vec::new();
+        for round in 0..5_000 {
+            let x = rng.next_u64() % 200;
+            match rng.next_u64() % 4 {
+                0 | 1 => {
+                    list.insert(x);
+                    let at = model.partition_point(|&y| y <= x);
+                    model.insert(at, x);
+                }
+                2 => {
+                    let removed = list.remove(&x);
+                    let expected = model.binary_search(&x).ok().map(|_| {
+                        let at = model.partition_point(|&y| y < x);
+                        model.remove(at)
+                    });
+                    assert_eq!(removed, expected);
+                }
+                _ => {
+                    if x.is_multiple_of(2) {
+                        assert_eq!(list.pop_front(), (!model.is_empty()).then(|| model.remove(0)));
+                    } else {
+                        assert_eq!(list.pop_back(), model.pop());
+                    }
+                }
+            }
+            if round % 100 == 0 {
+                check(&list);
+            }
+            let i = (rng.next_u64() as usize) % (model.len() + 1);
+            assert_eq!(list.get_index(i), model.get(i));
+        }
+        check(&list);
+        assert!(list.iter().eq(&model));
+    }
+
+    #[test]
+    fn traits() {
+        let list: SkipList<i32> = [5, 3, 4].into_iter().collect();
+        let mut cloned = list.clone();
+        assert_eq!(list, cloned);
+        assert_eq!(format!("{:?}", list), "[3, 4, 5]");
+
+        cloned.extend([1, 9]);
+        check(&cloned);
+        assert_ne!(list, cloned);
+        assert!(cloned < list);
+        assert_eq!(cloned.cmp(&list), core::cmp::Ordering::Less);
+
+        let mut map = std::collections::HashMap::new();
+        map.insert(list, "list");
+        let key: SkipList<i32> = [3, 4, 5].into_iter().collect();
+        assert_eq!(map.get(&key), Some(&"list"));
+        assert_eq!(SkipList::<i32>::default().len(), 0);
+    }
+
+    #[test]
+    fn long_drop() {
+        let list: SkipList<i32> = (0..100_000).collect();
+        assert_eq!(list.get_index(99_999), Some(&99_999));
+        drop(list);
+    }
+
+    #[test]
+    fn send_sync() {
+        crate::assert_impl!(Send, Sync: SkipList<i32>, super::Iter<i32>);
+        crate::assert_impl!(Send: super::IntoIter<i32>);
+    }
+
+    crate::assert_covariant!(_covariant: SkipList, _covariant_iter: super::Iter<'i>, _covariant_into_iter: super::IntoIter);
+}
@@ -1,6 +1,155 @@
 // Too many linked lists 的第四章，不可变栈
 // 可以构建JS Promise那样的链、或者git版本分支、撤销恢复操作
-use std::rc::Rc;
+// 要跨线程共享版本用sync::List，它是Arc的版本
+use alloc::rc::Rc;
+
+// IntoIter/TryIntoIter和sync版本只差Rc还是Arc，写一次，两个模块各展开一份
+// 展开的地方要有List和Node，$ptr是那边用的引用计数指针
+macro_rules! owning_iters {
+    ($ptr:ident) => {
+        // 拿着剩下的链表，中途drop也走List的非递归drop
+        pub struct IntoIter<T>(List<T>);
+
+        pub struct TryIntoIter<T>(List<T>);
+
+        impl<T> List<T> {
+            // 和into_iter一样按栈顶到栈底取出，但碰到共享的节点就停下，不需要T: Clone
+            // 停下之后用into_rest拿回剩下（共享的那部分）链表
+            pub fn try_into_iter(self) -> TryIntoIter<T> {
+                TryIntoIter(self)
+            }
+        }
+
+        // 只有这个链表拥有的节点直接把值搬出来，节点也顺手释放
+        // 碰到别的版本也在用的节点就只能clone，之后的节点也都是共享的（至少被那个共享节点指着）
+        impl<T: Clone> IntoIterator for List<T> {
+            type Item = T;
+            type IntoIter = IntoIter<T>;
+
+            fn into_iter(self) -> Self::IntoIter {
+                IntoIter(self)
+            }
+        }
+
+        impl<T: Clone> Iterator for IntoIter<T> {
+            type Item = T;
+
+            fn next(&mut self) -> Option<Self::Item> {
+                self.0.head.take().map(|node| {
+                    match $ptr::try_unwrap(node) {
+                        Ok(mut node) => {
+                            self.0.head = node.next.take();
+                            node.elem
+                        }
+                        Err(node) => {
+                            self.0.head = node.next.clone();
+                            let elem = node.elem.clone();
+                            // 同Drop，Arc的别的持有者可能同时放手，用into_inner；next已经另外拿着了，拆掉这个节点不会往下递归
+                            drop($ptr::into_inner(node));
+                            elem
+                        }
+                    }
+                })
+            }
+        }
+
+        impl<T> TryIntoIter<T> {
+            // 还没取出的部分，迭代停下时就是从第一个共享节点开始的链表
+            pub fn into_rest(mut self) -> List<T> {
+                List { head: self.0.head.take() }
+            }
+        }
+
+        impl<T> Iterator for TryIntoIter<T> {
+            type Item = T;
+
+            fn next(&mut self) -> Option<Self::Item> {
+                let node = self.0.head.take()?;
+                match $ptr::try_unwrap(node) {
+                    Ok(mut node) => {
+                        self.0.head = node.next.take();
+                        Some(node.elem)
+                    }
+                    Err(node) => {
+                        // 放回去，之后一直返回None
+                        self.0.head = Some(node);
+                        None
+                    }
+                }
+            }
+        }
+    };
+}
+
+// 上面两个迭代器的测试，也是两边共用；展开在test模块里，用的是super::List
+#[cfg(test)]
+macro_rules! owning_iter_tests {
+    () => {
+        #[test]
+        fn into_iter() {
+            let list = List::new().append(1).append(2).append(3);
+            assert_eq!(list.into_iter().collect::<Vec<_>>(), [3, 2, 1]);
+
+            // 4、5只属于branch，搬出来；2、1和base共享，clone出来，base不受影响
+            let base = List::new().append(1).append(2);
+            let branch = base.append(4).append(5);
+            assert_eq!(branch.into_iter().collect::<Vec<_>>(), [5, 4, 2, 1]);
+            assert_eq!(base.iter().copied().collect::<Vec<_>>(), [2, 1]);
+        }
+
+        #[test]
+        fn into_iter_moves_unique() {
+            use std::{ cell::Cell, rc::Rc };
+
+            // 记下clone了几次
+            struct Counted(i32, Rc<Cell<usize>>);
+            impl Clone for Counted {
+                fn clone(&self) -> Self {
+                    self.1.set(self.1.get() + 1);
+                    Counted(self.0, self.1.clone())
+                }
+            }
+
+            let clones = Rc::new(Cell::new(0));
+            let base = List::new().append(Counted(1, clones.clone()));
+            let branch = base.append(Counted(2, clones.clone())).append(Counted(3, clones.clone()));
+            let values: Vec<_> = branch
+                .into_iter()
+                .map(|c| c.0)
+                .collect();
+            assert_eq!(values, [3, 2, 1]);
+            assert_eq!(clones.get(), 1);
+        }
+
+        #[test]
+        fn try_into_iter() {
+            let base = List::new().append(1).append(2);
+            let branch = base.append(3).append(4);
+
+            let mut iter = branch.try_into_iter();
+            assert_eq!(iter.next(), Some(4));
+            assert_eq!(iter.next(), Some(3));
+            assert_eq!(iter.next(), None);
+            assert_eq!(iter.next(), None);
+            let rest = iter.into_rest();
+            assert_eq!(rest.iter().copied().collect::<Vec<_>>(), [2, 1]);
+            drop(base);
+
+            // base没了，rest独占，可以全部取出
+            let mut iter = rest.try_into_iter();
+            assert_eq!(iter.next(), Some(2));
+            assert_eq!(iter.next(), Some(1));
+            assert_eq!(iter.next(), None);
+            assert_eq!(iter.into_rest().head(), None);
+        }
+    };
+}
+
+#[cfg(test)]
+pub(crate) use owning_iter_tests;
+
+#[cfg(target_has_atomic = "ptr")]
+pub mod sync;
 
 pub struct List<T> {
     head: Link<T>,
@@ -29,7 +178,7 @@ impl<T> List<T> {
         List {
             head: Some(
                 Rc::new(Node {
-                    elem: elem,
+                    elem,
                     next: self.head.clone(),
                 })
             ),
@@ -47,6 +196,16 @@ impl<T> List<T> {
     pub fn head(&self) -> Option<&T> {
         self.head.as_ref().map(|node| &node.elem)
     }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        self.into_iter()
+    }
+}
+
+impl<T> Default for List<T> {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl<T> Drop for List<T> {
@@ -68,7 +227,7 @@ impl<'a, T> IntoIterator for &'a List<T> {
 
     fn into_iter(self) -> Self::IntoIter {
         Iter {
-            next: self.head.as_ref().map(|node| &**node),
+            next: self.head.as_deref(),
         }
     }
 }
@@ -78,16 +237,36 @@ impl<'a, T> Iterator for Iter<'a, T> {
 
     fn next(&mut self) -> Option<Self::Item> {
         self.next.map(|node| {
-            self.next = node.next.as_ref().map(|node| &**node);
+            self.next = node.next.as_deref();
             &node.elem
         })
     }
 }
 
+owning_iters!(Rc);
+
+impl<T> crate::PersistentStack for List<T> {
+    type Item = T;
+
+    fn append(&self, elem: T) -> Self {
+        self.append(elem)
+    }
+
+    fn tail(&self) -> Self {
+        self.tail()
+    }
+
+    fn head(&self) -> Option<&T> {
+        self.head()
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::List;
 
+    crate::persistent_stack_conformance!(persistent_stack, super::List<i32>);
+
     #[test]
     fn basics() {
         let list = List::new();
@@ -118,5 +297,10 @@ mod test {
         assert_eq!(iter.next(), Some(&3));
         assert_eq!(iter.next(), Some(&2));
         assert_eq!(iter.next(), Some(&1));
+
+        let mut iter = list.iter();
+        assert_eq!(iter.next(), Some(&3));
     }
+
+    crate::third::owning_iter_tests!();
 }
@@ -0,0 +1,195 @@
+// 不可变栈的多线程版本
+// 和上一层的List一模一样，只是Rc换成了Arc
+// Rc的引用计数不是原子的，不能跨线程；Arc是原子的，T: Send + Sync时整个List就能Send + Sync
+// 于是一个版本可以交给别的线程继续往上append，两边共享同一段尾巴
+use alloc::sync::Arc;
+
+pub struct List<T> {
+    head: Link<T>,
+}
+
+type Link<T> = Option<Arc<Node<T>>>;
+
+struct Node<T> {
+    elem: T,
+    next: Link<T>,
+}
+
+pub struct Iter<'a, T> {
+    next: Option<&'a Node<T>>,
+}
+
+impl<T> List<T> {
+    pub fn new() -> Self {
+        List { head: None }
+    }
+
+    pub fn append(&self, elem: T) -> List<T> {
+        List {
+            head: Some(
+                Arc::new(Node {
+                    elem,
+                    next: self.head.clone(),
+                })
+            ),
+        }
+    }
+
+    pub fn tail(&self) -> List<T> {
+        List {
+            head: self.head.as_ref().and_then(|node| node.next.clone()),
+        }
+    }
+
+    pub fn head(&self) -> Option<&T> {
+        self.head.as_ref().map(|node| &node.elem)
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        self.into_iter()
+    }
+}
+
+impl<T> Default for List<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// 同样不能递归drop
+// 别的线程可能同时在drop共享的尾巴：两边的try_unwrap可能都失败，最后放手的那个Arc就递归drop了
+// into_inner保证正好有一个持有者拿到节点，由它接着往下拆
+impl<T> Drop for List<T> {
+    fn drop(&mut self) {
+        let mut head = self.head.take();
+        while let Some(node) = head {
+            match Arc::into_inner(node) {
+                Some(mut node) => {
+                    head = node.next.take();
+                }
+                None => {
+                    break;
+                }
+            }
+        }
+    }
+}
+
+impl<'a, T> IntoIterator for &'a List<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        Iter {
+            next: self.head.as_deref(),
+        }
+    }
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next.map(|node| {
+            self.next = node.next.as_deref();
+            &node.elem
+        })
+    }
+}
+
+owning_iters!(Arc);
+
+impl<T> crate::PersistentStack for List<T> {
+    type Item = T;
+
+    fn append(&self, elem: T) -> Self {
+        self.append(elem)
+    }
+
+    fn tail(&self) -> Self {
+        self.tail()
+    }
+
+    fn head(&self) -> Option<&T> {
+        self.head()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::List;
+    #[cfg(feature = "std")]
+    use std::thread;
+
+    crate::persistent_stack_conformance!(persistent_stack, super::List<i32>);
+
+    #[test]
+    fn basics() {
+        let list = List::new();
+        assert_eq!(list.head(), None);
+
+        let list = list.append(1).append(2).append(3);
+        assert_eq!(list.head(), Some(&3));
+
+        let list = list.tail();
+        assert_eq!(list.head(), Some(&2));
+
+        let list = list.tail();
+        assert_eq!(list.head(), Some(&1));
+
+        let list = list.tail();
+        assert_eq!(list.head(), None);
+
+        let list = list.tail();
+        assert_eq!(list.head(), None);
+    }
+
+    #[test]
+    fn iter() {
+        let list = List::new().append(1).append(2).append(3);
+
+        let mut iter = list.iter();
+        assert_eq!(iter.next(), Some(&3));
+        assert_eq!(iter.next(), Some(&2));
+        assert_eq!(iter.next(), Some(&1));
+        assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    fn send_sync() {
+        crate::assert_impl!(Send, Sync: List<i32>, super::Iter<'_, i32>);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn share_between_threads() {
+        let base = List::new().append(1).append(2);
+
+        // 每个线程在同一个base上长出自己的分支，然后各自丢掉
+        let handles: Vec<_> = (0..8)
+            .map(|t| {
+                let base = base.tail().append(2);
+                thread::spawn(move || {
+                    let mut list = base;
+                    for i in 0..10_000 {
+                        list = list.append(t * 10_000 + i);
+                    }
+                    let sum: i32 = list.iter().take(3).sum();
+                    (list.head().copied(), sum)
+                })
+            })
+            .collect();
+
+        for (t, handle) in handles.into_iter().enumerate() {
+            let t = t as i32;
+            let (head, sum) = handle.join().unwrap();
+            assert_eq!(head, Some(t * 10_000 + 9_999));
+            assert_eq!(sum, 3 * (t * 10_000 + 9_998));
+        }
+
+        // 各个分支都drop了，共享的尾巴还在
+        assert_eq!(base.iter().copied().collect::<Vec<_>>(), [2, 1]);
+    }
+
+    crate::third::owning_iter_tests!();
+}
@@ -0,0 +1,1350 @@
+// 展开链表（unrolled linked list）
+// sixth的每个节点只放一个元素，外加两个指针；元素小的时候指针比元素还大，遍历时每一步都可能缓存未命中
+// 这里每个节点放一个定长数组，最多N个元素，节点内是连续的，遍历基本是顺序读内存
+//
+//     front -> [1 2 3 _] <-> [4 5 _ _] <-> [6 7 8 9] <- back
+//
+// 占用率靠自动拆分、合并来保证：
+// 1.没有空节点
+// 2.除了front和back两个节点，每个节点至少有N / 2个元素
+// 往满的节点里插入就对半拆开；删除后不够N / 2就和邻居合并，合并不下就从邻居借一半
+//
+// 接口和sixth一样：两头进出、三种迭代器、带ghost的CursorMut、split/splice
+
+use alloc::boxed::Box;
+use core::{
+    cmp::Ordering,
+    fmt::{ self, Debug },
+    hash::{ Hash, Hasher },
+    marker::PhantomData,
+    mem::{ self, MaybeUninit },
+    ptr::{ self, NonNull },
+};
+
+pub struct UnrolledList<T, const N: usize = 16> {
+    front: Link<T, N>,
+    back: Link<T, N>,
+    len: usize,
+    _boo: PhantomData<T>,
+}
+
+type Link<T, const N: usize> = Option<NonNull<Node<T, N>>>;
+
+// 元素的位置：哪个节点的第几个
+type Loc<T, const N: usize> = (NonNull<Node<T, N>>, usize);
+
+struct Node<T, const N: usize> {
+    front: Link<T, N>,
+    back: Link<T, N>,
+    // elems[..len]是初始化了的
+    len: usize,
+    elems: [MaybeUninit<T>; N],
+}
+
+pub struct Iter<'a, T, const N: usize = 16> {
+    front: Option<Loc<T, N>>,
+    back: Option<Loc<T, N>>,
+    len: usize,
+    _boo: PhantomData<&'a T>,
+}
+
+pub struct IterMut<'a, T, const N: usize = 16> {
+    front: Option<Loc<T, N>>,
+    back: Option<Loc<T, N>>,
+    len: usize,
+    _boo: PhantomData<&'a mut T>,
+}
+
+pub struct IntoIter<T, const N: usize = 16> {
+    list: UnrolledList<T, N>,
+}
+
+// 和sixth::CursorMut一样，cur为None时在ghost上
+pub struct CursorMut<'a, T, const N: usize = 16> {
+    list: &'a mut UnrolledList<T, N>,
+    cur: Option<Loc<T, N>>,
+    index: Option<usize>,
+}
+
+impl<T, const N: usize> Node<T, N> {
+    fn alloc() -> NonNull<Self> {
+        NonNull::from(
+            Box::leak(
+                Box::new(Node {
+                    front: None,
+                    back: None,
+                    len: 0,
+                    elems: [const { MaybeUninit::uninit() }; N],
+                })
+            )
+        )
+    }
+
+    // SAFETY: node活着，i <= N
+    unsafe fn slot(node: NonNull<Self>, i: usize) -> *mut T {
+        ptr::addr_of_mut!((*node.as_ptr()).elems).cast::<T>().add(i)
+    }
+
+    // 节点内插入，节点必须没满
+    unsafe fn insert(node: NonNull<Self>, i: usize, elem: T) {
+        let len = (*node.as_ptr()).len;
+        debug_assert!(len < N && i <= len);
+        ptr::copy(Self::slot(node, i), Self::slot(node, i + 1), len - i);
+        ptr::write(Self::slot(node, i), elem);
+        (*node.as_ptr()).len += 1;
+    }
+
+    unsafe fn remove(node: NonNull<Self>, i: usize) -> T {
+        let len = (*node.as_ptr()).len;
+        debug_assert!(i < len);
+        let elem = ptr::read(Self::slot(node, i));
+        ptr::copy(Self::slot(node, i + 1), Self::slot(node, i), len - i - 1);
+        (*node.as_ptr()).len -= 1;
+        elem
+    }
+}
+
+// 把src[src_at..src_at + count]搬到dst[dst_at..]，两边的其余元素前后挪开、合拢
+// track是调用者关心的某个元素的位置（一般是光标），跟着元素一起更新
+unsafe fn transfer<T, const N: usize>(
+    src: NonNull<Node<T, N>>,
+    src_at: usize,
+    dst: NonNull<Node<T, N>>,
+    dst_at: usize,
+    count: usize,
+    track: &mut Option<Loc<T, N>>
+) {
+    if count == 0 {
+        return;
+    }
+    let src_len = (*src.as_ptr()).len;
+    let dst_len = (*dst.as_ptr()).len;
+    debug_assert!(src != dst && src_at + count <= src_len && dst_len + count <= N);
+
+    ptr::copy(Node::slot(dst, dst_at), Node::slot(dst, dst_at + count), dst_len - dst_at);
+    ptr::copy_nonoverlapping(Node::slot(src, src_at), Node::slot(dst, dst_at), count);
+    ptr::copy(
+        Node::slot(src, src_at + count),
+        Node::slot(src, src_at),
+        src_len - src_at - count
+    );
+    (*src.as_ptr()).len -= count;
+    (*dst.as_ptr()).len += count;
+
+    if let Some((node, i)) = track {
+        if *node == src && *i >= src_at {
+            if *i < src_at + count {
+                *node = dst;
+                *i = dst_at + (*i - src_at);
+            } else {
+                *i -= count;
+            }
+        } else if *node == dst && *i >= dst_at {
+            *i += count;
+        }
+    }
+}
+
+impl<T, const N: usize> UnrolledList<T, N> {
+    pub fn new() -> Self {
+        const {
+            assert!(N >= 2, "UnrolledList needs at least two elements per node");
+        }
+        UnrolledList {
+            front: None,
+            back: None,
+            len: 0,
+            _boo: PhantomData,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn front(&self) -> Option<&T> {
+        unsafe { self.front.map(|node| &*Node::slot(node, 0)) }
+    }
+
+    pub fn front_mut(&mut self) -> Option<&mut T> {
+        unsafe { self.front.map(|node| &mut *Node::slot(node, 0)) }
+    }
+
+    pub fn back(&self) -> Option<&T> {
+        unsafe { self.back.map(|node| &*Node::slot(node, (*node.as_ptr()).len - 1)) }
+    }
+
+    pub fn back_mut(&mut self) -> Option<&mut T> {
+        unsafe { self.back.map(|node| &mut *Node::slot(node, (*node.as_ptr()).len - 1)) }
+    }
+
+    // 两头满了就新开一个节点，不拆老的，这样一直往一头push时节点都是满的
+    pub fn push_front(&mut self, elem: T) {
+        unsafe {
+            let node = match self.front {
+                Some(front) if (*front.as_ptr()).len < N => front,
+                _ => {
+                    let new = Node::alloc();
+                    self.link_before(self.front, new);
+                    new
+                }
+            };
+            Node::insert(node, 0, elem);
+        }
+        self.len += 1;
+    }
+
+    pub fn push_back(&mut self, elem: T) {
+        unsafe {
+            let node = match self.back {
+                Some(back) if (*back.as_ptr()).len < N => back,
+                _ => {
+                    let new = Node::alloc();
+                    self.link_after(self.back, new);
+                    new
+                }
+            };
+            Node::insert(node, (*node.as_ptr()).len, elem);
+        }
+        self.len += 1;
+    }
+
+    pub fn pop_front(&mut self) -> Option<T> {
+        let front = self.front?;
+        unsafe { Some(self.remove_at(front, 0, &mut None)) }
+    }
+
+    pub fn pop_back(&mut self) -> Option<T> {
+        let back = self.back?;
+        unsafe { Some(self.remove_at(back, (*back.as_ptr()).len - 1, &mut None)) }
+    }
+
+    pub fn clear(&mut self) {
+        // drop会把节点和元素都释放掉
+        drop(mem::take(self));
+    }
+
+    // 把other整个接到后面，other变空
+    // 只动接口处的两个节点，和链表长度无关
+    pub fn append(&mut self, other: &mut Self) {
+        unsafe { self.join(other, &mut None) }
+    }
+
+    // 把other整个接到前面，other变空
+    pub fn prepend(&mut self, other: &mut Self) {
+        mem::swap(self, other);
+        unsafe { self.join(other, &mut None) }
+    }
+
+    // 从at处分成两半，self留[0, at)，返回[at, len)
+    pub fn split_off(&mut self, at: usize) -> Self {
+        assert!(at <= self.len, "Cannot split off at a nonexistent index");
+        if at == self.len {
+            return Self::new();
+        }
+        unsafe {
+            let loc = self.locate(at);
+            self.split_chain(loc, at, &mut None)
+        }
+    }
+
+    pub fn iter(&self) -> Iter<'_, T, N> {
+        Iter {
+            front: self.front.map(|node| (node, 0)),
+            back: self.back.map(|node| unsafe { (node, (*node.as_ptr()).len - 1) }),
+            len: self.len,
+            _boo: PhantomData,
+        }
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, T, N> {
+        IterMut {
+            front: self.front.map(|node| (node, 0)),
+            back: self.back.map(|node| unsafe { (node, (*node.as_ptr()).len - 1) }),
+            len: self.len,
+            _boo: PhantomData,
+        }
+    }
+
+    pub fn cursor_mut(&mut self) -> CursorMut<'_, T, N> {
+        CursorMut {
+            list: self,
+            cur: None,
+            index: None,
+        }
+    }
+
+    pub fn cursor_front_mut(&mut self) -> CursorMut<'_, T, N> {
+        CursorMut {
+            cur: self.front.map(|node| (node, 0)),
+            index: self.front.map(|_| 0),
+            list: self,
+        }
+    }
+
+    pub fn cursor_back_mut(&mut self) -> CursorMut<'_, T, N> {
+        CursorMut {
+            cur: self.back.map(|node| unsafe { (node, (*node.as_ptr()).len - 1) }),
+            index: self.len.checked_sub(1),
+            list: self,
+        }
+    }
+
+    // 节点操作 ======================================
+    // 把new接到at后面，at为None表示接到最前面
+    unsafe fn link_after(&mut self, at: Link<T, N>, new: NonNull<Node<T, N>>) {
+        let next = match at {
+            Some(at) => (*at.as_ptr()).back.replace(new),
+            None => self.front.replace(new),
+        };
+        (*new.as_ptr()).front = at;
+        (*new.as_ptr()).back = next;
+        match next {
+            Some(next) => {
+                (*next.as_ptr()).front = Some(new);
+            }
+            None => {
+                self.back = Some(new);
+            }
+        }
+    }
+
+    // 把new接到at前面，at为None表示接到最后面
+    unsafe fn link_before(&mut self, at: Link<T, N>, new: NonNull<Node<T, N>>) {
+        let prev = match at {
+            Some(at) => (*at.as_ptr()).front,
+            None => self.back,
+        };
+        self.link_after(prev, new);
+    }
+
+    // 摘下一个空节点并释放
+    unsafe fn free_node(&mut self, node: NonNull<Node<T, N>>) {
+        let node = Box::from_raw(node.as_ptr());
+        debug_assert_eq!(node.len, 0);
+        match node.front {
+            Some(prev) => {
+                (*prev.as_ptr()).back = node.back;
+            }
+            None => {
+                self.front = node.back;
+            }
+        }
+        match node.back {
+            Some(next) => {
+                (*next.as_ptr()).front = node.front;
+            }
+            None => {
+                self.back = node.front;
+            }
+        }
+    }
+
+    // node[at..]挪到紧跟着的新节点里，返回新节点
+    unsafe fn split_node(
+        &mut self,
+        node: NonNull<Node<T, N>>,
+        at: usize,
+        track: &mut Option<Loc<T, N>>
+    ) -> NonNull<Node<T, N>> {
+        let new = Node::alloc();
+        self.link_after(Some(node), new);
+        transfer(node, at, new, 0, (*node.as_ptr()).len - at, track);
+        new
+    }
+
+    // 在node的pos处插入，满了先对半拆
+    unsafe fn insert_at(
+        &mut self,
+        mut node: NonNull<Node<T, N>>,
+        mut pos: usize,
+        elem: T,
+        track: &mut Option<Loc<T, N>>
+    ) {
+        if (*node.as_ptr()).len == N {
+            let new = self.split_node(node, N / 2, track);
+            if pos > N / 2 {
+                node = new;
+                pos -= N / 2;
+            }
+        }
+        Node::insert(node, pos, elem);
+        if let Some((at, i)) = track {
+            if *at == node && *i >= pos {
+                *i += 1;
+            }
+        }
+        self.len += 1;
+    }
+
+    // 移除node[pos]
+    // track如果正指着被删的元素，就改成指向它后面那个（没有就是None，也就是ghost）
+    unsafe fn remove_at(
+        &mut self,
+        node: NonNull<Node<T, N>>,
+        pos: usize,
+        track: &mut Option<Loc<T, N>>
+    ) -> T {
+        let elem = Node::remove(node, pos);
+        if let Some((at, i)) = *track {
+            if at == node && i > pos {
+                *track = Some((at, i - 1));
+            } else if at == node && i == pos && pos == (*node.as_ptr()).len {
+                *track = (*node.as_ptr()).back.map(|next| (next, 0));
+            }
+        }
+        self.len -= 1;
+        self.rebalance(node, track);
+        elem
+    }
+
+    // node刚少了元素，按开头说的规则处理
+    unsafe fn rebalance(&mut self, node: NonNull<Node<T, N>>, track: &mut Option<Loc<T, N>>) {
+        let len = (*node.as_ptr()).len;
+        if len == 0 {
+            self.free_node(node);
+            return;
+        }
+        if len >= N / 2 {
+            return;
+        }
+
+        let prev = (*node.as_ptr()).front;
+        let next = (*node.as_ptr()).back;
+        if let Some(next) = next {
+            if len + (*next.as_ptr()).len <= N {
+                transfer(next, 0, node, len, (*next.as_ptr()).len, track);
+                self.free_node(next);
+                return;
+            }
+        }
+        if let Some(prev) = prev {
+            let prev_len = (*prev.as_ptr()).len;
+            if prev_len + len <= N {
+                transfer(node, 0, prev, prev_len, len, track);
+                self.free_node(node);
+                return;
+            }
+        }
+        // 两边都合并不下，说明两边都比较满；两头的节点不受限制，中间的就从后面借到一样多
+        if let (Some(_), Some(next)) = (prev, next) {
+            let total = len + (*next.as_ptr()).len;
+            transfer(next, 0, node, len, total / 2 - len, track);
+        }
+    }
+
+    // 两条链刚接在一起，left和right成了邻居，可能都很空
+    unsafe fn fix_junction(
+        &mut self,
+        left: NonNull<Node<T, N>>,
+        right: NonNull<Node<T, N>>,
+        track: &mut Option<Loc<T, N>>
+    ) {
+        let left_len = (*left.as_ptr()).len;
+        let right_len = (*right.as_ptr()).len;
+        let total = left_len + right_len;
+        if total <= N {
+            // 合成一个，合出来的可能还是不够，再按删除后的规则处理一次
+            transfer(right, 0, left, left_len, right_len, track);
+            self.free_node(right);
+            self.rebalance(left, track);
+        } else if left_len < N / 2 {
+            transfer(right, 0, left, left_len, total / 2 - left_len, track);
+        } else if right_len < N / 2 {
+            let count = total / 2 - right_len;
+            transfer(left, left_len - count, right, 0, count, track);
+        }
+    }
+
+    // other的节点整条接到后面
+    unsafe fn join(&mut self, other: &mut Self, track: &mut Option<Loc<T, N>>) {
+        let (Some(other_front), Some(other_back)) = (other.front.take(), other.back.take()) else {
+            return;
+        };
+        let len = mem::replace(&mut other.len, 0);
+        match self.back {
+            Some(back) => {
+                (*back.as_ptr()).back = Some(other_front);
+                (*other_front.as_ptr()).front = Some(back);
+                self.back = Some(other_back);
+                self.len += len;
+                self.fix_junction(back, other_front, track);
+            }
+            None => {
+                self.front = Some(other_front);
+                self.back = Some(other_back);
+                self.len = len;
+            }
+        }
+    }
+
+    // 从loc处断开，self留下前面的front_len个，返回从loc开始的后半段
+    unsafe fn split_chain(
+        &mut self,
+        loc: Loc<T, N>,
+        front_len: usize,
+        track: &mut Option<Loc<T, N>>
+    ) -> Self {
+        let (mut first, offset) = loc;
+        if offset > 0 {
+            first = self.split_node(first, offset, track);
+        }
+
+        let mut output = Self::new();
+        output.len = self.len - front_len;
+        output.front = Some(first);
+        output.back = self.back;
+        self.len = front_len;
+        match (*first.as_ptr()).front.take() {
+            Some(prev) => {
+                (*prev.as_ptr()).back = None;
+                self.back = Some(prev);
+            }
+            None => {
+                self.front = None;
+                self.back = None;
+            }
+        }
+        output
+    }
+
+    // 第index个元素在哪，从近的那头按节点跳着找
+    unsafe fn locate(&self, index: usize) -> Loc<T, N> {
+        debug_assert!(index < self.len);
+        if index < self.len / 2 {
+            let mut node = self.front.unwrap();
+            let mut skipped = 0;
+            while skipped + (*node.as_ptr()).len <= index {
+                skipped += (*node.as_ptr()).len;
+                node = (*node.as_ptr()).back.unwrap();
+            }
+            (node, index - skipped)
+        } else {
+            let mut node = self.back.unwrap();
+            // node之后（不含）一共有几个元素
+            let mut after = 0;
+            while self.len - after - (*node.as_ptr()).len > index {
+                after += (*node.as_ptr()).len;
+                node = (*node.as_ptr()).front.unwrap();
+            }
+            (node, index - (self.len - after - (*node.as_ptr()).len))
+        }
+    }
+}
+
+impl<T, const N: usize> Drop for UnrolledList<T, N> {
+    fn drop(&mut self) {
+        let mut cur = self.front.take();
+        self.back = None;
+        while let Some(node) = cur {
+            unsafe {
+                let node = Box::from_raw(node.as_ptr());
+                cur = node.back;
+                let elems = ptr::slice_from_raw_parts_mut(
+                    node.elems.as_ptr().cast::<T>().cast_mut(),
+                    node.len
+                );
+                ptr::drop_in_place(elems);
+            }
+        }
+    }
+}
+
+// 迭代器 =========================================
+impl<T, const N: usize> IntoIterator for UnrolledList<T, N> {
+    type IntoIter = IntoIter<T, N>;
+    type Item = T;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter { list: self }
+    }
+}
+
+impl<T, const N: usize> Iterator for IntoIter<T, N> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.list.pop_front()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.list.len, Some(self.list.len))
+    }
+}
+
+impl<T, const N: usize> DoubleEndedIterator for IntoIter<T, N> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.list.pop_back()
+    }
+}
+
+impl<T, const N: usize> ExactSizeIterator for IntoIter<T, N> {
+    fn len(&self) -> usize {
+        self.list.len
+    }
+}
+
+// 位置的前后移动，迭代器和光标共用
+unsafe fn next_loc<T, const N: usize>((node, i): Loc<T, N>) -> Option<Loc<T, N>> {
+    if i + 1 < (*node.as_ptr()).len {
+        Some((node, i + 1))
+    } else {
+        (*node.as_ptr()).back.map(|next| (next, 0))
+    }
+}
+
+unsafe fn prev_loc<T, const N: usize>((node, i): Loc<T, N>) -> Option<Loc<T, N>> {
+    if i > 0 {
+        Some((node, i - 1))
+    } else {
+        (*node.as_ptr()).front.map(|prev| (prev, (*prev.as_ptr()).len - 1))
+    }
+}
+
+impl<'a, T, const N: usize> IntoIterator for &'a UnrolledList<T, N> {
+    type IntoIter = Iter<'a, T, N>;
+    type Item = &'a T;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T, const N: usize> Iterator for Iter<'a, T, N> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        self.front.map(|loc| unsafe {
+            self.len -= 1;
+            self.front = next_loc(loc);
+            &*Node::slot(loc.0, loc.1)
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<'a, T, const N: usize> DoubleEndedIterator for Iter<'a, T, N> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        self.back.map(|loc| unsafe {
+            self.len -= 1;
+            self.back = prev_loc(loc);
+            &*Node::slot(loc.0, loc.1)
+        })
+    }
+}
+
+impl<'a, T, const N: usize> ExactSizeIterator for Iter<'a, T, N> {
+    fn len(&self) -> usize {
+        self.len
+    }
+}
+
+impl<'a, T, const N: usize> IntoIterator for &'a mut UnrolledList<T, N> {
+    type IntoIter = IterMut<'a, T, N>;
+    type Item = &'a mut T;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+impl<'a, T, const N: usize> Iterator for IterMut<'a, T, N> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        self.front.map(|loc| unsafe {
+            self.len -= 1;
+            self.front = next_loc(loc);
+            &mut *Node::slot(loc.0, loc.1)
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<'a, T, const N: usize> DoubleEndedIterator for IterMut<'a, T, N> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        self.back.map(|loc| unsafe {
+            self.len -= 1;
+            self.back = prev_loc(loc);
+            &mut *Node::slot(loc.0, loc.1)
+        })
+    }
+}
+
+impl<'a, T, const N: usize> ExactSizeIterator for IterMut<'a, T, N> {
+    fn len(&self) -> usize {
+        self.len
+    }
+}
+
+// 光标 ===========================================
+// 元素会在节点之间搬来搬去，光标的位置跟着track一起更新，index只在语义上变化时才动
+impl<'a, T, const N: usize> CursorMut<'a, T, N> {
+    pub fn index(&self) -> Option<usize> {
+        self.index
+    }
+
+    pub fn move_next(&mut self) {
+        match self.cur {
+            Some(loc) => {
+                self.cur = unsafe { next_loc(loc) };
+                self.index = match self.cur {
+                    Some(_) => self.index.map(|i| i + 1),
+                    None => None,
+                };
+            }
+            None => {
+                self.cur = self.list.front.map(|node| (node, 0));
+                self.index = self.cur.map(|_| 0);
+            }
+        }
+    }
+
+    pub fn move_prev(&mut self) {
+        match self.cur {
+            Some(loc) => {
+                self.cur = unsafe { prev_loc(loc) };
+                self.index = match self.cur {
+                    Some(_) => self.index.map(|i| i - 1),
+                    None => None,
+                };
+            }
+            None => {
+                self.cur = self.list.back.map(|node| unsafe { (node, (*node.as_ptr()).len - 1) });
+                self.index = self.list.len.checked_sub(1);
+            }
+        }
+    }
+
+    pub fn current(&mut self) -> Option<&mut T> {
+        unsafe { self.cur.map(|(node, i)| &mut *Node::slot(node, i)) }
+    }
+
+    pub fn peek_next(&mut self) -> Option<&mut T> {
+        unsafe {
+            let next = match self.cur {
+                Some(loc) => next_loc(loc),
+                None => self.list.front.map(|node| (node, 0)),
+            };
+            next.map(|(node, i)| &mut *Node::slot(node, i))
+        }
+    }
+
+    pub fn peek_prev(&mut self) -> Option<&mut T> {
+        unsafe {
+            let prev = match self.cur {
+                Some(loc) => prev_loc(loc),
+                None => self.list.back.map(|node| (node, (*node.as_ptr()).len - 1)),
+            };
+            prev.map(|(node, i)| &mut *Node::slot(node, i))
+        }
+    }
+
+    // 在当前元素后插入，ghost上则插到front
+    // index不变
+    pub fn insert_after(&mut self, elem: T) {
+        match self.cur {
+            Some((node, i)) => unsafe { self.list.insert_at(node, i + 1, elem, &mut self.cur) },
+            None => self.list.push_front(elem),
+        }
+    }
+
+    // 在当前元素前插入，ghost上则插到back
+    // 当前元素往后挪了一位，所以index加1
+    pub fn insert_before(&mut self, elem: T) {
+        match self.cur {
+            Some((node, i)) => {
+                unsafe { self.list.insert_at(node, i, elem, &mut self.cur) }
+                *self.index.as_mut().unwrap() += 1;
+            }
+            None => self.list.push_back(elem),
+        }
+    }
+
+    // 移除当前元素，光标移到下一个（back方向）
+    // 下一个是ghost的话index变None，否则index不变
+    pub fn remove_current(&mut self) -> Option<T> {
+        let (node, i) = self.cur?;
+        let elem = unsafe { self.list.remove_at(node, i, &mut self.cur) };
+        if self.cur.is_none() {
+            self.index = None;
+        }
+        Some(elem)
+    }
+
+    // 返回当前元素之前的部分，光标留在原来的元素上，index变0
+    // ghost上则返回整个链表
+    pub fn split_before(&mut self) -> UnrolledList<T, N> {
+        let (Some(cur), Some(index)) = (self.cur, self.index) else {
+            return mem::take(self.list);
+        };
+        if index == 0 {
+            return UnrolledList::new();
+        }
+        let back = unsafe { self.list.split_chain(cur, index, &mut self.cur) };
+        self.index = Some(0);
+        mem::replace(self.list, back)
+    }
+
+    // 返回当前元素之后的部分，index不变
+    // ghost上则返回整个链表
+    pub fn split_after(&mut self) -> UnrolledList<T, N> {
+        let (Some(cur), Some(index)) = (self.cur, self.index) else {
+            return mem::take(self.list);
+        };
+        match unsafe { next_loc(cur) } {
+            Some(next) => unsafe { self.list.split_chain(next, index + 1, &mut self.cur) },
+            None => UnrolledList::new(),
+        }
+    }
+
+    // 插到当前元素前面，index加上插入的个数
+    // ghost上则接到back
+    pub fn splice_before(&mut self, mut input: UnrolledList<T, N>) {
+        let (Some(cur), Some(index)) = (self.cur, self.index) else {
+            self.list.append(&mut input);
+            return;
+        };
+        let input_len = input.len;
+        unsafe {
+            let mut front = if index > 0 {
+                let back = self.list.split_chain(cur, index, &mut self.cur);
+                mem::replace(self.list, back)
+            } else {
+                UnrolledList::new()
+            };
+            front.join(&mut input, &mut None);
+            front.join(self.list, &mut self.cur);
+            *self.list = front;
+        }
+        self.index = Some(index + input_len);
+    }
+
+    // 插到当前元素后面，index不变
+    // ghost上则接到front
+    pub fn splice_after(&mut self, mut input: UnrolledList<T, N>) {
+        let (Some(cur), Some(index)) = (self.cur, self.index) else {
+            self.list.prepend(&mut input);
+            return;
+        };
+        unsafe {
+            let mut rest = match next_loc(cur) {
+                Some(next) => self.list.split_chain(next, index + 1, &mut self.cur),
+                None => UnrolledList::new(),
+            };
+            self.list.join(&mut input, &mut self.cur);
+            self.list.join(&mut rest, &mut self.cur);
+        }
+    }
+}
+
+// ================================================
+impl<T, const N: usize> Default for UnrolledList<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Clone, const N: usize> Clone for UnrolledList<T, N> {
+    fn clone(&self) -> Self {
+        self.iter().cloned().collect()
+    }
+}
+
+impl<T, const N: usize> Extend<T> for UnrolledList<T, N> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for item in iter {
+            self.push_back(item);
+        }
+    }
+}
+
+impl<T, const N: usize> FromIterator<T> for UnrolledList<T, N> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut list = Self::new();
+        list.extend(iter);
+        list
+    }
+}
+
+impl<T: Debug, const N: usize> Debug for UnrolledList<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self).finish()
+    }
+}
+
+// 只比较元素，节点怎么分的不算
+impl<T: PartialEq, const N: usize> PartialEq for UnrolledList<T, N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.into_iter().eq(other)
+    }
+}
+
+impl<T: Eq, const N: usize> Eq for UnrolledList<T, N> {}
+
+impl<T: PartialOrd, const N: usize> PartialOrd for UnrolledList<T, N> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.into_iter().partial_cmp(other)
+    }
+}
+
+impl<T: Ord, const N: usize> Ord for UnrolledList<T, N> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.into_iter().cmp(other)
+    }
+}
+
+impl<T: Hash, const N: usize> Hash for UnrolledList<T, N> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.len().hash(state);
+        for item in self {
+            item.hash(state);
+        }
+    }
+}
+
+unsafe impl<T: Send, const N: usize> Send for UnrolledList<T, N> {}
+unsafe impl<T: Sync, const N: usize> Sync for UnrolledList<T, N> {}
+
+unsafe impl<'a, T: Sync, const N: usize> Send for Iter<'a, T, N> {}
+unsafe impl<'a, T: Sync, const N: usize> Sync for Iter<'a, T, N> {}
+
+unsafe impl<'a, T: Send, const N: usize> Send for IterMut<'a, T, N> {}
+unsafe impl<'a, T: Sync, const N: usize> Sync for IterMut<'a, T, N> {}
+
+// 通用接口 =======================================
+// 栈从front进出，队列从back进front出
+impl<T, const N: usize> crate::Stack for UnrolledList<T, N> {
+    type Item = T;
+
+    fn push(&mut self, elem: T) {
+        self.push_front(elem)
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        self.pop_front()
+    }
+}
+
+impl<T, const N: usize> crate::Queue for UnrolledList<T, N> {
+    type Item = T;
+
+    fn enqueue(&mut self, elem: T) {
+        self.push_back(elem)
+    }
+
+    fn dequeue(&mut self) -> Option<T> {
+        self.pop_front()
+    }
+}
+
+impl<T, const N: usize> crate::Deque for UnrolledList<T, N> {
+    type Item = T;
+
+    fn push_front(&mut self, elem: T) {
+        self.push_front(elem)
+    }
+
+    fn push_back(&mut self, elem: T) {
+        self.push_back(elem)
+    }
+
+    fn pop_front(&mut self) -> Option<T> {
+        self.pop_front()
+    }
+
+    fn pop_back(&mut self) -> Option<T> {
+        self.pop_back()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::UnrolledList;
+    use crate::test_util::{
+        to_vec,
+        XorShift,
+    };
+    use std::collections::VecDeque;
+
+    crate::stack_conformance!(stack, super::UnrolledList<i32, 4>);
+    crate::queue_conformance!(queue, super::UnrolledList<i32, 4>);
+    crate::deque_conformance!(deque, super::UnrolledList<i32>);
+
+    // 检查链接、长度，以及开头说的占用率规则，返回每个节点的元素个数
+    fn check<T, const N: usize>(list: &UnrolledList<T, N>) -> Vec<usize> {
+        let mut sizes = Vec::new();
+        let mut prev = None;
+        let mut cur = list.front;
+        unsafe {
+            while let Some(node) = cur {
+                assert_eq!((*node.as_ptr()).front, prev);
+                sizes.push((*node.as_ptr()).len);
+                prev = cur;
+                cur = (*node.as_ptr()).back;
+            }
+        }
+        assert_eq!(list.back, prev);
+        assert_eq!(sizes.iter().sum::<usize>(), list.len());
+        assert!(sizes.iter().all(|&n| 0 < n && n <= N), "{sizes:?}");
+        if sizes.len() > 2 {
+            assert!(sizes[1..sizes.len() - 1].iter().all(|&n| n >= N / 2), "{sizes:?}");
+        }
+        sizes
+    }
+
+    #[test]
+    fn basics() {
+        let mut list = UnrolledList::<i32, 4>::new();
+        assert_eq!(list.pop_front(), None);
+        assert_eq!(list.pop_back(), None);
+
+        for i in 0..10 {
+            list.push_back(i);
+        }
+        for i in 1..4 {
+            list.push_front(-i);
+        }
+        // 一直往一头push，节点都是满的
+        assert_eq!(check(&list), [3, 4, 4, 2]);
+        assert_eq!(list.front(), Some(&-3));
+        assert_eq!(list.back(), Some(&9));
+        *list.front_mut().unwrap() = -30;
+        *list.back_mut().unwrap() = 90;
+
+        assert_eq!(list.pop_front(), Some(-30));
+        assert_eq!(list.pop_back(), Some(90));
+        check(&list);
+        while list.len() > 1 {
+            list.pop_front();
+            check(&list);
+        }
+        assert_eq!(list.pop_back(), Some(8));
+        assert!(list.is_empty());
+        assert!(list.front.is_none() && list.back.is_none());
+
+        list.extend(0..20);
+        list.clear();
+        assert!(list.is_empty());
+        list.push_back(1);
+        assert_eq!(to_vec(&list), [1]);
+    }
+
+    #[test]
+    fn iter() {
+        let mut list: UnrolledList<i32, 3> = (0..10).collect();
+        check(&list);
+
+        let mut iter = list.iter();
+        assert_eq!(iter.len(), 10);
+        assert_eq!(iter.next(), Some(&0));
+        assert_eq!(iter.next_back(), Some(&9));
+        assert_eq!(iter.len(), 8);
+        assert_eq!(iter.rev().copied().collect::<Vec<_>>(), [8, 7, 6, 5, 4, 3, 2, 1]);
+
+        for elem in list.iter_mut() {
+            *elem *= 10;
+        }
+        let mut iter = list.iter_mut();
+        *iter.next_back().unwrap() += 1;
+        assert_eq!(iter.len(), 9);
+        assert_eq!(to_vec(&list), [0, 10, 20, 30, 40, 50, 60, 70, 80, 91]);
+
+        let mut iter = list.into_iter();
+        assert_eq!(iter.size_hint(), (10, Some(10)));
+        assert_eq!(iter.next_back(), Some(91));
+        assert_eq!(iter.next(), Some(0));
+        assert_eq!(iter.collect::<Vec<_>>(), [10, 20, 30, 40, 50, 60, 70, 80]);
+    }
+
+    #[test]
+    fn cursor_move_insert_remove() {
+        let mut list: UnrolledList<i32, 4> = (0..8).collect();
+        let mut cursor = list.cursor_mut();
+        assert_eq!(cursor.current(), None);
+        assert_eq!(cursor.peek_next(), Some(&mut 0));
+        assert_eq!(cursor.peek_prev(), Some(&mut 7));
+        cursor.move_prev();
+        assert_eq!(cursor.index(), Some(7));
+        cursor.move_next();
+        assert_eq!(cursor.index(), None);
+        cursor.move_next();
+        cursor.move_next();
+        cursor.move_next();
+        assert_eq!(cursor.current(), Some(&mut 2));
+        assert_eq!(cursor.index(), Some(2));
+
+        // 插到满节点里会拆开，光标要跟着元素走
+        cursor.insert_after(100);
+        assert_eq!(cursor.current(), Some(&mut 2));
+        assert_eq!(cursor.peek_next(), Some(&mut 100));
+        cursor.insert_before(200);
+        assert_eq!(cursor.current(), Some(&mut 2));
+        assert_eq!(cursor.index(), Some(3));
+        assert_eq!(cursor.peek_prev(), Some(&mut 200));
+
+        assert_eq!(cursor.remove_current(), Some(2));
+        assert_eq!(cursor.current(), Some(&mut 100));
+        assert_eq!(cursor.index(), Some(3));
+        assert_eq!(cursor.remove_current(), Some(100));
+        assert_eq!(cursor.current(), Some(&mut 3));
+        check(cursor.list);
+
+        let mut cursor = list.cursor_back_mut();
+        assert_eq!(cursor.remove_current(), Some(7));
+        assert_eq!(cursor.index(), None);
+        assert_eq!(cursor.current(), None);
+        cursor.insert_before(8);
+        cursor.insert_after(-1);
+        assert_eq!(to_vec(&list), [-1, 0, 1, 200, 3, 4, 5, 6, 8]);
+        check(&list);
+
+        // 一路删到空
+        let mut cursor = list.cursor_front_mut();
+        let mut removed = Vec::new();
+        while let Some(elem) = cursor.remove_current() {
+            removed.push(elem);
+            assert_eq!(cursor.index(), cursor.current().map(|_| 0));
+            check(cursor.list);
+        }
+        assert_eq!(removed, [-1, 0, 1, 200, 3, 4, 5, 6, 8]);
+        assert!(list.is_empty());
+    }
+
+    #[test]
+    fn split_splice() {
+        let mut list: UnrolledList<i32, 4> = (0..10).collect();
+        let mut tail = list.split_off(6);
+        check(&list);
+        check(&tail);
+        assert_eq!(to_vec(&list), [0, 1, 2, 3, 4, 5]);
+        assert_eq!(to_vec(&tail), [6, 7, 8, 9]);
+        assert!(list.split_off(6).is_empty());
+
+        list.append(&mut tail);
+        assert!(tail.is_empty());
+        check(&list);
+        let mut head = list.split_off(0);
+        assert!(list.is_empty());
+        list.prepend(&mut head);
+        assert_eq!(to_vec(&list), (0..10).collect::<Vec<_>>());
+
+        let mut cursor = list.cursor_front_mut();
+        cursor.move_next();
+        cursor.move_next();
+        cursor.move_next();
+        let before = cursor.split_before();
+        assert_eq!(to_vec(&before), [0, 1, 2]);
+        assert_eq!(cursor.index(), Some(0));
+        assert_eq!(cursor.current(), Some(&mut 3));
+        let after = cursor.split_after();
+        assert_eq!(to_vec(&after), [4, 5, 6, 7, 8, 9]);
+        assert_eq!(cursor.current(), Some(&mut 3));
+        assert!(cursor.split_after().is_empty());
+        assert!(cursor.split_before().is_empty());
+
+        cursor.splice_after(after);
+        cursor.splice_before(before);
+        assert_eq!(cursor.index(), Some(3));
+        assert_eq!(cursor.current(), Some(&mut 3));
+        check(cursor.list);
+        assert_eq!(to_vec(&list), (0..10).collect::<Vec<_>>());
+
+        // ghost上：splice_before接到后面，splice_after接到前面，split拿走全部
+        let mut cursor = list.cursor_mut();
+        cursor.splice_before((10..12).collect());
+        cursor.splice_after((-2..0).collect());
+        assert_eq!(cursor.index(), None);
+        let all = cursor.split_after();
+        assert_eq!(to_vec(&all), (-2..12).collect::<Vec<_>>());
+        assert!(list.is_empty());
+    }
+
+    #[test]
+    fn junctions_rebalance() {
+        // 两个很空的端点节点接在中间，要合并或者借元素
+        let mut left = UnrolledList::<i32, 4>::new();
+        left.extend(0..9);
+        let mut right = UnrolledList::<i32, 4>::new();
+        for i in (9..18).rev() {
+            right.push_front(i);
+        }
+        assert_eq!(check(&left), [4, 4, 1]);
+        assert_eq!(check(&right), [1, 4, 4]);
+        left.append(&mut right);
+        check(&left);
+        assert_eq!(to_vec(&left), (0..18).collect::<Vec<_>>());
+
+        // 光标所在的节点在接口处被搬动
+        let mut list: UnrolledList<i32, 4> = (0..9).collect();
+        let mut cursor = list.cursor_back_mut();
+        cursor.splice_after((9..10).collect());
+        cursor.splice_before((100..101).collect());
+        assert_eq!(cursor.current(), Some(&mut 8));
+        assert_eq!(cursor.index(), Some(9));
+        check(&list);
+        assert_eq!(to_vec(&list), [0, 1, 2, 3, 4, 5, 6, 7, 100, 8, 9]);
+    }
+
+    // 随机操作，和VecDeque对照
+    #[test]
+    fn random_ops() {
+        fn run<const N: usize>(seed: u64) {
+            let mut rng = XorShift(seed);
+            let mut rand = |n| rng.below(n);
+
+            let mut list = UnrolledList::<usize, N>::new();
+            let mut model = VecDeque::new();
+            let mut next = 0;
+            for _ in 0..2_000 {
+                let at = rand(model.len() + 1);
+                let mut cursor = list.cursor_front_mut();
+                for _ in 0..at {
+                    cursor.move_next();
+                }
+                // at == len时在ghost上
+                assert_eq!(cursor.index(), (at < model.len()).then_some(at));
+                match rand(8) {
+                    0 | 1 => {
+                        cursor.insert_before(next);
+                        model.insert(at, next);
+                        next += 1;
+                    }
+                    2 | 3 => {
+                        cursor.insert_after(next);
+                        model.insert(if at == model.len() { 0 } else { at + 1 }, next);
+                        next += 1;
+                    }
+                    4 | 5 => {
+                        assert_eq!(cursor.remove_current(), model.remove(at));
+                        assert_eq!(cursor.current().copied(), model.get(at).copied());
+                    }
+                    6 => {
+                        let mut rest = cursor.split_after();
+                        let expected: Vec<_> = if at == model.len() {
+                            model.drain(..).collect()
+                        } else {
+                            model.drain(at + 1..).collect()
+                        };
+                        assert_eq!(to_vec(&rest), expected);
+                        check(&rest);
+                        check(&list);
+                        // 再从某处接回去
+                        let mut cursor = list.cursor_front_mut();
+                        let at = rand(model.len() + 1);
+                        for _ in 0..at {
+                            cursor.move_next();
+                        }
+                        cursor.splice_before(std::mem::take(&mut rest));
+                        for (k, elem) in expected.into_iter().enumerate() {
+                            model.insert(at + k, elem);
+                        }
+                    }
+                    _ => {
+                        if rand(2) == 0 {
+                            assert_eq!(list.pop_front(), model.pop_front());
+                        } else {
+                            assert_eq!(list.pop_back(), model.pop_back());
+                        }
+                    }
+                }
+                check(&list);
+                assert_eq!(list.len(), model.len());
+            }
+            assert!(list.iter().eq(model.iter()));
+            assert!(list.iter().rev().eq(model.iter().rev()));
+        }
+
+        run::<2>(1);
+        run::<3>(2);
+        run::<4>(3);
+        run::<8>(4);
+        run::<16>(5);
+    }
+
+    #[test]
+    fn drops() {
+        use std::rc::Rc;
+
+        let counter = Rc::new(());
+        let mut list: UnrolledList<Rc<()>, 4> = (0..20).map(|_| counter.clone()).collect();
+        let mut cursor = list.cursor_front_mut();
+        cursor.move_next();
+        drop(cursor.remove_current());
+        let rest = cursor.split_after();
+        drop(rest);
+        // 剩下第0个和光标上的那个
+        assert_eq!(Rc::strong_count(&counter), 3);
+        list.clear();
+        assert_eq!(Rc::strong_count(&counter), 1);
+
+        let list: UnrolledList<Rc<()>, 4> = (0..20).map(|_| counter.clone()).collect();
+        drop(list);
+        assert_eq!(Rc::strong_count(&counter), 1);
+    }
+
+    #[test]
+    fn traits() {
+        let list: UnrolledList<i32, 4> = (0..10).collect();
+        let mut cloned = list.clone();
+        assert_eq!(list, cloned);
+        assert_eq!(format!("{:?}", cloned), "[0, 1, 2, 3, 4, 5, 6, 7, 8, 9]");
+        cloned.pop_back();
+        assert_ne!(list, cloned);
+        assert!(cloned < list);
+
+        // 节点分法不同也相等
+        let mut split = (0..10).collect::<UnrolledList<i32, 4>>();
+        split.cursor_front_mut().insert_after(100);
+        split.cursor_front_mut().split_after();
+        let mut rest: UnrolledList<i32, 4> = (1..10).collect();
+        split.append(&mut rest);
+        assert_eq!(split, list);
+
+        let mut map = std::collections::HashMap::new();
+        map.insert(list, "list");
+        assert_eq!(map.get(&split), Some(&"list"));
+        assert_eq!(UnrolledList::<i32>::default().len(), 0);
+    }
+
+    #[test]
+    fn zero_sized() {
+        let mut list: UnrolledList<(), 4> = std::iter::repeat_n((), 10).collect();
+        assert_eq!(check(&list), [4, 4, 2]);
+        let mut cursor = list.cursor_front_mut();
+        cursor.move_next();
+        cursor.insert_after(());
+        assert_eq!(cursor.remove_current(), Some(()));
+        assert_eq!(list.len(), 10);
+    }
+
+    #[test]
+    fn send_sync() {
+        crate::assert_impl!(Send, Sync: UnrolledList<i32>, super::Iter<i32>, super::IterMut<i32>);
+        crate::assert_impl!(Send: super::IntoIter<i32>);
+    }
+
+    crate::assert_covariant!(_covariant: UnrolledList, _covariant_iter: super::Iter<'i>, _covariant_into_iter: super::IntoIter);
+}
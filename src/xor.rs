@@ -0,0 +1,699 @@
+// 异或链表（XOR linked list）
+// 双链表每个节点要两个链接，这里只存一个：link = prev ^ next
+// 从某一头往里走时手上总有上一个节点，next = link ^ prev，反方向同理
+//
+// 代价是不能从任意节点出发（不知道它的邻居），所以没有光标，只能两头进出、从两头遍历
+// 好处除了省一个字，还有reverse是O(1)：交换head和tail就行，节点里的异或值是对称的
+//
+// 指针异或之后就不是指针了，再异或回来的整数没有provenance，strict provenance下不能解引用
+// 所以节点不单独分配，都放在一个Vec里，链接存的是下标（从1开始，0代表没有，异或0不改变值）
+// 删掉的槽位串成空闲链表，link存下一个空闲槽位，链表空了就整块清掉
+// 没空的时候空闲槽位只会被复用、不会还回去，要还掉内存得调shrink_to_fit
+// 这样整个模块除了MaybeUninit的读写，不需要碰裸指针
+
+use alloc::vec::Vec;
+use core::{ fmt::{ self, Debug }, marker::PhantomData, mem::{ self, MaybeUninit }, ptr::NonNull };
+
+pub struct XorList<T> {
+    slots: Vec<Slot<T>>,
+    head: usize,
+    tail: usize,
+    // 空闲链表头
+    free: usize,
+    len: usize,
+}
+
+struct Slot<T> {
+    // 在链表里时是prev ^ next，空闲时是下一个空闲槽位
+    link: usize,
+    elem: MaybeUninit<T>,
+}
+
+pub struct IntoIter<T>(XorList<T>);
+
+// 两头各记着(上一个, 下一个要产出的)，靠len判断相遇
+pub struct Iter<'a, T> {
+    slots: &'a [Slot<T>],
+    front: (usize, usize),
+    back: (usize, usize),
+    len: usize,
+}
+
+// 要同时交出多个元素的&mut，所以拿的是槽位数组的裸指针
+pub struct IterMut<'a, T> {
+    slots: NonNull<Slot<T>>,
+    front: (usize, usize),
+    back: (usize, usize),
+    len: usize,
+    _boo: PhantomData<&'a mut T>,
+}
+
+impl<T> XorList<T> {
+    pub fn new() -> Self {
+        XorList {
+            slots: Vec::new(),
+            head: 0,
+            tail: 0,
+            free: 0,
+            len: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    // 交换两头，节点不用动
+    pub fn reverse(&mut self) {
+        mem::swap(&mut self.head, &mut self.tail);
+    }
+
+    pub fn push_front(&mut self, elem: T) {
+        let id = self.alloc(elem, self.head);
+        match self.head {
+            // 原来的head的prev从0变成id
+            0 => self.tail = id,
+            head => self.slot_mut(head).link ^= id,
+        }
+        self.head = id;
+        self.len += 1;
+    }
+
+    pub fn pop_front(&mut self) -> Option<T> {
+        if self.head == 0 {
+            return None;
+        }
+        let id = self.head;
+        // head的prev是0，link就是next
+        let next = self.slot(id).link;
+        match next {
+            0 => self.tail = 0,
+            next => self.slot_mut(next).link ^= id,
+        }
+        self.head = next;
+        self.len -= 1;
+        Some(self.release(id))
+    }
+
+    // 另一头的操作就是反过来的这一头
+    pub fn push_back(&mut self, elem: T) {
+        self.reverse();
+        self.push_front(elem);
+        self.reverse();
+    }
+
+    pub fn pop_back(&mut self) -> Option<T> {
+        self.reverse();
+        let elem = self.pop_front();
+        self.reverse();
+        elem
+    }
+
+    pub fn front(&self) -> Option<&T> {
+        self.get(self.head)
+    }
+
+    pub fn front_mut(&mut self) -> Option<&mut T> {
+        self.get_mut(self.head)
+    }
+
+    pub fn back(&self) -> Option<&T> {
+        self.get(self.tail)
+    }
+
+    pub fn back_mut(&mut self) -> Option<&mut T> {
+        self.get_mut(self.tail)
+    }
+
+    // 按链表顺序把元素搬进一个紧凑的新数组，第i个元素在第i个槽位，空闲槽位都不要了
+    // 链接全部重算；元素只是按位搬动，不调用用户代码，中途不会panic
+    pub fn shrink_to_fit(&mut self) {
+        let mut slots = Vec::with_capacity(self.len);
+        let (mut prev, mut cur) = (0, self.head);
+        for i in 1..=self.len {
+            let slot = self.slot(cur);
+            let next = slot.link ^ prev;
+            // SAFETY: cur在链表上，元素是初始化了的；读出后旧数组整个丢掉，不会再读一次
+            let elem = unsafe { slot.elem.assume_init_read() };
+            // 新的下标就是i，前一个是i - 1，后一个是i + 1（最后一个没有）
+            let link = (i - 1) ^ (if i == self.len { 0 } else { i + 1 });
+            slots.push(Slot { link, elem: MaybeUninit::new(elem) });
+            (prev, cur) = (cur, next);
+        }
+        // 旧数组里都是MaybeUninit，丢掉不会drop元素
+        self.slots = slots;
+        self.head = if self.len == 0 { 0 } else { 1 };
+        self.tail = self.len;
+        self.free = 0;
+    }
+
+    pub fn clear(&mut self) {
+        while self.pop_front().is_some() {}
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            slots: &self.slots,
+            front: (0, self.head),
+            back: (0, self.tail),
+            len: self.len,
+        }
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut {
+            slots: NonNull::new(self.slots.as_mut_ptr()).unwrap(),
+            front: (0, self.head),
+            back: (0, self.tail),
+            len: self.len,
+            _boo: PhantomData,
+        }
+    }
+
+    // 槽位 ===========================================
+    fn slot(&self, id: usize) -> &Slot<T> {
+        &self.slots[id - 1]
+    }
+
+    fn slot_mut(&mut self, id: usize) -> &mut Slot<T> {
+        &mut self.slots[id - 1]
+    }
+
+    fn get(&self, id: usize) -> Option<&T> {
+        // SAFETY: 链表上的槽位都是初始化了的
+        (id != 0).then(|| unsafe { self.slot(id).elem.assume_init_ref() })
+    }
+
+    fn get_mut(&mut self, id: usize) -> Option<&mut T> {
+        (id != 0).then(|| unsafe { self.slot_mut(id).elem.assume_init_mut() })
+    }
+
+    // 优先用空闲槽位
+    fn alloc(&mut self, elem: T, link: usize) -> usize {
+        let slot = Slot {
+            link,
+            elem: MaybeUninit::new(elem),
+        };
+        match self.free {
+            0 => {
+                self.slots.push(slot);
+                self.slots.len()
+            }
+            id => {
+                self.free = mem::replace(self.slot_mut(id), slot).link;
+                id
+            }
+        }
+    }
+
+    // 取出元素，槽位还给空闲链表；链表已经空了的话整块清掉，免得空闲链表越积越长
+    fn release(&mut self, id: usize) -> T {
+        let free = self.free;
+        let slot = self.slot_mut(id);
+        slot.link = free;
+        // SAFETY: id刚从链表上摘下来，元素还在，读出后槽位就当作未初始化
+        let elem = unsafe { slot.elem.assume_init_read() };
+        self.free = id;
+        if self.len == 0 {
+            self.slots.clear();
+            self.free = 0;
+        }
+        elem
+    }
+}
+
+impl<T> Drop for XorList<T> {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+// 迭代器 =========================================
+impl<T> IntoIterator for XorList<T> {
+    type IntoIter = IntoIter<T>;
+    type Item = T;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter(self)
+    }
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.pop_front()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.0.len, Some(self.0.len))
+    }
+}
+
+impl<T> DoubleEndedIterator for IntoIter<T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.0.pop_back()
+    }
+}
+
+impl<T> ExactSizeIterator for IntoIter<T> {
+    fn len(&self) -> usize {
+        self.0.len
+    }
+}
+
+impl<'a, T> IntoIterator for &'a XorList<T> {
+    type IntoIter = Iter<'a, T>;
+    type Item = &'a T;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T> Iter<'a, T> {
+    // 从(prev, cur)走一步，返回cur
+    fn step(&self, (prev, cur): &mut (usize, usize)) -> &'a T {
+        let slot = &self.slots[*cur - 1];
+        *prev = mem::replace(cur, slot.link ^ *prev);
+        unsafe { slot.elem.assume_init_ref() }
+    }
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        self.len -= 1;
+        let mut front = self.front;
+        let elem = self.step(&mut front);
+        self.front = front;
+        Some(elem)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for Iter<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        self.len -= 1;
+        let mut back = self.back;
+        let elem = self.step(&mut back);
+        self.back = back;
+        Some(elem)
+    }
+}
+
+impl<'a, T> ExactSizeIterator for Iter<'a, T> {
+    fn len(&self) -> usize {
+        self.len
+    }
+}
+
+// Iter只是共享借用，可以随便复制
+impl<'a, T> Clone for Iter<'a, T> {
+    fn clone(&self) -> Self {
+        Iter { ..*self }
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut XorList<T> {
+    type IntoIter = IterMut<'a, T>;
+    type Item = &'a mut T;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+impl<'a, T> IterMut<'a, T> {
+    // SAFETY: cur是链表上的槽位，每个槽位只会被产出一次（靠len保证两头不重叠）
+    unsafe fn step(&mut self, (prev, cur): &mut (usize, usize)) -> &'a mut T {
+        let slot = self.slots.add(*cur - 1).as_ptr();
+        *prev = mem::replace(cur, (*slot).link ^ *prev);
+        (*slot).elem.assume_init_mut()
+    }
+}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        self.len -= 1;
+        let mut front = self.front;
+        let elem = unsafe { self.step(&mut front) };
+        self.front = front;
+        Some(elem)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for IterMut<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        self.len -= 1;
+        let mut back = self.back;
+        let elem = unsafe { self.step(&mut back) };
+        self.back = back;
+        Some(elem)
+    }
+}
+
+impl<'a, T> ExactSizeIterator for IterMut<'a, T> {
+    fn len(&self) -> usize {
+        self.len
+    }
+}
+
+unsafe impl<'a, T: Send> Send for IterMut<'a, T> {}
+unsafe impl<'a, T: Sync> Sync for IterMut<'a, T> {}
+
+// ================================================
+impl<T> Default for XorList<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Clone> Clone for XorList<T> {
+    fn clone(&self) -> Self {
+        self.iter().cloned().collect()
+    }
+}
+
+impl<T> Extend<T> for XorList<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for item in iter {
+            self.push_back(item);
+        }
+    }
+}
+
+impl<T> FromIterator<T> for XorList<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut list = Self::new();
+        list.extend(iter);
+        list
+    }
+}
+
+impl<T: Debug> Debug for XorList<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self).finish()
+    }
+}
+
+impl<T: PartialEq> PartialEq for XorList<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.into_iter().eq(other)
+    }
+}
+
+impl<T: Eq> Eq for XorList<T> {}
+
+// 通用接口 =======================================
+// 栈从front进出，队列从back进front出
+impl<T> crate::Stack for XorList<T> {
+    type Item = T;
+
+    fn push(&mut self, elem: T) {
+        self.push_front(elem)
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        self.pop_front()
+    }
+}
+
+impl<T> crate::Queue for XorList<T> {
+    type Item = T;
+
+    fn enqueue(&mut self, elem: T) {
+        self.push_back(elem)
+    }
+
+    fn dequeue(&mut self) -> Option<T> {
+        self.pop_front()
+    }
+}
+
+impl<T> crate::Deque for XorList<T> {
+    type Item = T;
+
+    fn push_front(&mut self, elem: T) {
+        self.push_front(elem)
+    }
+
+    fn push_back(&mut self, elem: T) {
+        self.push_back(elem)
+    }
+
+    fn pop_front(&mut self) -> Option<T> {
+        self.pop_front()
+    }
+
+    fn pop_back(&mut self) -> Option<T> {
+        self.pop_back()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::XorList;
+    use crate::test_util::to_vec;
+
+    crate::stack_conformance!(stack, super::XorList<i32>);
+    crate::queue_conformance!(queue, super::XorList<i32>);
+    crate::deque_conformance!(deque, super::XorList<i32>);
+
+    // 从两头各走一遍，走到的槽位要正好反过来；空闲槽位和链表上的加起来是全部槽位
+    fn check<T>(list: &XorList<T>) {
+        let walk = |from| {
+            let mut ids = Vec::new();
+            let (mut prev, mut cur) = (0, from);
+            while cur != 0 {
+                ids.push(cur);
+                (prev, cur) = (cur, list.slot(cur).link ^ prev);
+            }
+            ids
+        };
+        let forward = walk(list.head);
+        let mut backward = walk(list.tail);
+        backward.reverse();
+        assert_eq!(forward, backward);
+        assert_eq!(forward.len(), list.len());
+
+        let mut free = Vec::new();
+        let mut cur = list.free;
+        while cur != 0 {
+            assert!(!forward.contains(&cur), "slot {cur} is both free and linked");
+            free.push(cur);
+            cur = list.slot(cur).link;
+        }
+        assert_eq!(forward.len() + free.len(), list.slots.len());
+    }
+
+    fn links<T>(list: &XorList<T>) -> Vec<usize> {
+        list.slots.iter().map(|slot| slot.link).collect()
+    }
+
+    #[test]
+    fn basics() {
+        let mut list = XorList::new();
+        assert_eq!(list.pop_front(), None);
+        assert_eq!(list.pop_back(), None);
+        assert_eq!(list.front(), None);
+
+        list.push_back(1);
+        list.push_front(0);
+        list.push_back(2);
+        check(&list);
+        // 槽位按分配顺序编号：0、1、2分别在槽位2、1、3，link是左右两个槽位号的异或
+        // 两头的节点有一边是0，link就是另一边的槽位号
+        assert_eq!(links(&list), [2 ^ 3, 1, 1]);
+
+        // reverse只交换两头，槽位一个都不动
+        list.reverse();
+        assert_eq!(links(&list), [2 ^ 3, 1, 1]);
+        assert_eq!((list.head, list.tail), (3, 2));
+        assert_eq!(list.front(), Some(&2));
+        assert_eq!(list.back(), Some(&0));
+        *list.front_mut().unwrap() = 20;
+        *list.back_mut().unwrap() = 10;
+        list.push_front(3);
+        check(&list);
+        assert_eq!(to_vec(&list), [3, 20, 1, 10]);
+
+        assert_eq!(list.pop_back(), Some(10));
+        assert_eq!(list.pop_front(), Some(3));
+        check(&list);
+        assert_eq!(list.pop_front(), Some(20));
+        assert_eq!(list.pop_back(), Some(1));
+        assert_eq!(list.pop_back(), None);
+        assert!(list.is_empty());
+        // 空了之后槽位整块清掉
+        assert!(list.slots.is_empty());
+        assert_eq!(list.free, 0);
+    }
+
+    #[test]
+    fn reuse_slots() {
+        let mut list: XorList<i32> = (0..8).collect();
+        list.pop_front();
+        list.pop_back();
+        list.pop_front();
+        assert_eq!(list.slots.len(), 8);
+        list.push_back(8);
+        list.push_front(-1);
+        list.push_front(-2);
+        assert_eq!(list.slots.len(), 8);
+        list.push_front(-3);
+        assert_eq!(list.slots.len(), 9);
+        check(&list);
+        assert_eq!(to_vec(&list), [-3, -2, -1, 2, 3, 4, 5, 6, 8]);
+    }
+
+    #[test]
+    fn shrink_to_fit() {
+        let mut list: XorList<i32> = (0..10).collect();
+        list.reverse();
+        for _ in 0..3 {
+            list.pop_front();
+            list.pop_back();
+        }
+        // 还剩4个，空闲的6个槽位一直占着
+        assert_eq!(list.slots.len(), 10);
+        list.shrink_to_fit();
+        check(&list);
+        assert_eq!(list.slots.len(), 4);
+        assert_eq!(list.slots.capacity(), 4);
+        assert_eq!(list.free, 0);
+        assert_eq!(to_vec(&list), [6, 5, 4, 3]);
+        assert_eq!(list.iter().rev().copied().collect::<Vec<_>>(), [3, 4, 5, 6]);
+
+        // 整理之后照常进出
+        list.push_front(7);
+        list.push_back(2);
+        assert_eq!(to_vec(&list), [7, 6, 5, 4, 3, 2]);
+        assert_eq!(list.pop_back(), Some(2));
+        assert_eq!(list.pop_front(), Some(7));
+
+        let mut empty = XorList::<i32>::new();
+        empty.shrink_to_fit();
+        assert!(empty.slots.is_empty());
+        empty.push_back(1);
+        assert_eq!(to_vec(&empty), [1]);
+    }
+
+    #[test]
+    fn reverse() {
+        let mut list: XorList<i32> = (0..5).collect();
+        list.reverse();
+        assert_eq!(to_vec(&list), [4, 3, 2, 1, 0]);
+        list.push_front(5);
+        list.push_back(-1);
+        assert_eq!(list.pop_back(), Some(-1));
+        list.reverse();
+        assert_eq!(to_vec(&list), [0, 1, 2, 3, 4, 5]);
+
+        let mut empty = XorList::<i32>::new();
+        empty.reverse();
+        assert!(empty.iter().next().is_none());
+    }
+
+    #[test]
+    fn iter() {
+        let mut list: XorList<i32> = (0..6).collect();
+        let mut iter = list.iter();
+        assert_eq!(iter.len(), 6);
+        assert_eq!(iter.next(), Some(&0));
+        assert_eq!(iter.next_back(), Some(&5));
+        let rest = iter.clone();
+        assert_eq!(iter.next_back(), Some(&4));
+        assert_eq!(iter.next(), Some(&1));
+        assert_eq!(iter.next(), Some(&2));
+        assert_eq!(iter.next_back(), Some(&3));
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.next_back(), None);
+        assert_eq!(rest.rev().copied().collect::<Vec<_>>(), [4, 3, 2, 1]);
+
+        for elem in &mut list {
+            *elem *= 10;
+        }
+        let mut iter = list.iter_mut();
+        let front = iter.next().unwrap();
+        let back = iter.next_back().unwrap();
+        *front += 1;
+        *back += 1;
+        assert_eq!(iter.len(), 4);
+        assert_eq!(to_vec(&list), [1, 10, 20, 30, 40, 51]);
+
+        let mut iter = list.into_iter();
+        assert_eq!(iter.next_back(), Some(51));
+        assert_eq!(iter.next(), Some(1));
+        assert_eq!(iter.len(), 4);
+        assert_eq!(iter.collect::<Vec<_>>(), [10, 20, 30, 40]);
+
+        assert!(XorList::<i32>::new().iter_mut().next().is_none());
+    }
+
+    #[test]
+    fn traits() {
+        let list: XorList<i32> = (0..4).collect();
+        assert_eq!(format!("{:?}", list), "[0, 1, 2, 3]");
+        assert_eq!(format!("{:?}", XorList::<i32>::default()), "[]");
+
+        let mut other = list.clone();
+        assert_eq!(list, other);
+        other.reverse();
+        assert_ne!(list, other);
+        other.reverse();
+        other.pop_back();
+        assert_ne!(list, other);
+        other.push_back(3);
+        assert_eq!(list, other);
+    }
+
+    #[test]
+    fn drops() {
+        use std::rc::Rc;
+
+        let counter = Rc::new(());
+        let mut list: XorList<_> = (0..10).map(|_| counter.clone()).collect();
+        drop(list.pop_front());
+        drop(list.pop_back());
+        assert_eq!(Rc::strong_count(&counter), 9);
+        let mut iter = list.into_iter();
+        iter.next();
+        drop(iter);
+        assert_eq!(Rc::strong_count(&counter), 1);
+    }
+
+    #[test]
+    fn send_sync() {
+        crate::assert_impl!(Send, Sync: XorList<i32>, super::Iter<i32>, super::IterMut<i32>);
+        crate::assert_impl!(Send: super::IntoIter<i32>);
+    }
+
+    crate::assert_covariant!(_covariant: XorList, _covariant_iter: super::Iter<'i>);
+}